@@ -99,11 +99,31 @@ fn parser_large_benchmark(c: &mut Criterion) {
     });
 }
 
+fn parser_nested_benchmark(c: &mut Criterion) {
+    // Expressão com 500 níveis de parênteses, caso patológico para a pilha
+    let depth = 500;
+    let nested_source = format!(
+        "func main() -> int {{\n    return {}1{};\n}}\n",
+        "(".repeat(depth),
+        " + 1)".repeat(depth)
+    );
+
+    c.bench_function("parser_nested", |b| {
+        b.iter(|| {
+            let mut lexer = Lexer::new(black_box(&nested_source));
+            let tokens = lexer.tokenize().unwrap();
+            let mut parser = Parser::new(tokens);
+            parser.parse().unwrap();
+        });
+    });
+}
+
 criterion_group!(
     benches,
     parser_benchmark,
     parser_simple_benchmark,
     parser_expression_benchmark,
-    parser_large_benchmark
+    parser_large_benchmark,
+    parser_nested_benchmark
 );
 criterion_main!(benches); 
\ No newline at end of file
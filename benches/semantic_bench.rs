@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ruscompile::{Lexer, Parser, SemanticAnalyzer};
+
+fn semantic_nested_benchmark(c: &mut Criterion) {
+    // Muitas globais e blocos profundamente aninhados: entrar em cada
+    // escopo não deve custar proporcionalmente aos símbolos externos
+    let mut source = String::new();
+    for i in 0..200 {
+        source.push_str(&format!("var g{}: int = {};\n", i, i));
+    }
+    source.push_str("func main() -> int {\n    var total: int = 0;\n");
+    for i in 0..200 {
+        source.push_str(&format!("{{\n    var x{}: int = g{} + total;\n    total = x{};\n", i, i, i));
+    }
+    source.push_str(&"}\n".repeat(200));
+    source.push_str("    return total;\n}\n");
+
+    let mut lexer = Lexer::new(&source);
+    let tokens = lexer.tokenize().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+
+    c.bench_function("semantic_nested_blocks", |b| {
+        b.iter(|| {
+            let mut analyzer = SemanticAnalyzer::new();
+            analyzer.analyze(black_box(&program)).unwrap();
+        });
+    });
+}
+
+criterion_group!(benches, semantic_nested_benchmark);
+criterion_main!(benches);
@@ -1,11 +1,31 @@
-use std::fmt;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use crate::error::{CompilerError, CompilerResult};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
     pub statements: Vec<Statement>,
 }
 
+impl Program {
+    /// Reconstrói um programa a partir da AST serializada por `to_json`
+    #[cfg(feature = "std")]
+    pub fn from_json(json: &str) -> CompilerResult<Program> {
+        serde_json::from_str(json).map_err(CompilerError::JsonError)
+    }
+
+    /// Serializa a AST em JSON
+    #[cfg(feature = "std")]
+    pub fn to_json(&self) -> CompilerResult<String> {
+        serde_json::to_string(self).map_err(CompilerError::JsonError)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Statement {
     Expression(ExpressionStatement),
@@ -15,7 +35,32 @@ pub enum Statement {
     While(WhileStatement),
     Function(FunctionStatement),
     Return(ReturnStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
     Block(BlockStatement),
+    Empty(EmptyStatement),
+    /// Comando descartado por um erro de sintaxe em `parse_with_recovery`
+    Error(ErrorNode),
+}
+
+impl Statement {
+    /// Posição da declaração no código fonte
+    pub fn location(&self) -> &Location {
+        match self {
+            Statement::Expression(stmt) => &stmt.location,
+            Statement::Declaration(stmt) => &stmt.location,
+            Statement::Assignment(stmt) => &stmt.location,
+            Statement::If(stmt) => &stmt.location,
+            Statement::While(stmt) => &stmt.location,
+            Statement::Function(stmt) => &stmt.location,
+            Statement::Return(stmt) => &stmt.location,
+            Statement::Break(stmt) => &stmt.location,
+            Statement::Continue(stmt) => &stmt.location,
+            Statement::Block(stmt) => &stmt.location,
+            Statement::Empty(stmt) => &stmt.location,
+            Statement::Error(stmt) => &stmt.location,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -29,6 +74,9 @@ pub struct DeclarationStatement {
     pub name: String,
     pub var_type: Type,
     pub initializer: Option<Expression>,
+    /// Declarada com `const`: o inicializador é avaliado em tempo de compilação
+    #[serde(default)]
+    pub is_const: bool,
     pub location: Location,
 }
 
@@ -51,6 +99,9 @@ pub struct IfStatement {
 pub struct WhileStatement {
     pub condition: Expression,
     pub body: Box<Statement>,
+    /// Rótulo do laço (`externo: while ...`), alvo de `break externo`
+    #[serde(default)]
+    pub label: Option<String>,
     pub location: Location,
 }
 
@@ -60,6 +111,8 @@ pub struct FunctionStatement {
     pub parameters: Vec<Parameter>,
     pub return_type: Type,
     pub body: BlockStatement,
+    /// Comentários `///` que precedem a função, unidos por quebras de linha
+    pub doc: Option<String>,
     pub location: Location,
 }
 
@@ -69,12 +122,43 @@ pub struct ReturnStatement {
     pub location: Location,
 }
 
+/// `break`: sai do laço `while` mais interno, ou do laço com o rótulo
+/// indicado (`break externo;`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakStatement {
+    #[serde(default)]
+    pub label: Option<String>,
+    pub location: Location,
+}
+
+/// `continue`: volta à condição do laço `while` mais interno, ou do laço com
+/// o rótulo indicado (`continue externo;`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContinueStatement {
+    #[serde(default)]
+    pub label: Option<String>,
+    pub location: Location,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockStatement {
     pub statements: Vec<Statement>,
     pub location: Location,
 }
 
+/// `;` sozinho, como em `while (c);`: não faz nada
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmptyStatement {
+    pub location: Location,
+}
+
+/// Lugar de um trecho com erro de sintaxe, mantido para que a árvore
+/// continue completa; as fases seguintes devem ignorá-lo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorNode {
+    pub location: Location,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
@@ -89,7 +173,11 @@ pub enum Expression {
     Binary(BinaryExpression),
     Unary(UnaryExpression),
     Call(CallExpression),
+    IndirectCall(IndirectCallExpression),
     Assignment(AssignmentExpression),
+    Cast(CastExpression),
+    /// Expressão descartada por um erro de sintaxe em `parse_with_recovery`
+    Error(ErrorNode),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -126,6 +214,22 @@ pub struct CallExpression {
     pub location: Location,
 }
 
+/// Chamada através de uma expressão que produz uma função, como `(f)(1)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndirectCallExpression {
+    pub callee: Box<Expression>,
+    pub arguments: Vec<Expression>,
+    pub location: Location,
+}
+
+/// Conversão explícita de tipo, como `x as float`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastExpression {
+    pub expression: Box<Expression>,
+    pub target_type: Type,
+    pub location: Location,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AssignmentExpression {
     pub target: String,
@@ -138,10 +242,11 @@ pub enum Literal {
     Integer(i64),
     Float(f64),
     Boolean(bool),
+    Char(char),
     String(String),
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BinaryOperator {
     Add,
     Subtract,
@@ -165,17 +270,21 @@ pub enum UnaryOperator {
     Minus,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Type {
     Int,
     Float,
     Bool,
+    Char,
     String,
     Void,
     Function {
         parameters: Vec<Type>,
         return_type: Box<Type>,
     },
+    /// Tipo omitido em `var x = ...`: a análise semântica o deduz do
+    /// inicializador
+    Inferred,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -183,6 +292,95 @@ pub struct Location {
     pub line: usize,
     pub column: usize,
     pub length: usize,
+    /// Arquivo de origem informado por uma diretiva `//line`
+    pub file: Option<String>,
+}
+
+/// Percurso mutável da AST, usado por transformações como as do otimizador.
+///
+/// Cada método pode substituir o nó recebido no lugar (`*expression = ...`);
+/// a implementação padrão apenas visita os filhos com as funções `walk_*_mut`.
+pub trait VisitorMut {
+    fn visit_program_mut(&mut self, program: &mut Program) {
+        walk_program_mut(self, program);
+    }
+
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression);
+    }
+}
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+    for statement in &mut program.statements {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Expression(stmt) => visitor.visit_expression_mut(&mut stmt.expression),
+        Statement::Declaration(stmt) => {
+            if let Some(initializer) = &mut stmt.initializer {
+                visitor.visit_expression_mut(initializer);
+            }
+        }
+        Statement::Assignment(stmt) => visitor.visit_expression_mut(&mut stmt.value),
+        Statement::If(stmt) => {
+            visitor.visit_expression_mut(&mut stmt.condition);
+            visitor.visit_statement_mut(&mut stmt.then_branch);
+            if let Some(else_branch) = &mut stmt.else_branch {
+                visitor.visit_statement_mut(else_branch);
+            }
+        }
+        Statement::While(stmt) => {
+            visitor.visit_expression_mut(&mut stmt.condition);
+            visitor.visit_statement_mut(&mut stmt.body);
+        }
+        Statement::Function(stmt) => {
+            for statement in &mut stmt.body.statements {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+        Statement::Return(stmt) => {
+            if let Some(value) = &mut stmt.value {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        Statement::Break(_) | Statement::Continue(_) | Statement::Empty(_) | Statement::Error(_) => {}
+        Statement::Block(stmt) => {
+            for statement in &mut stmt.statements {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Literal(_) | Expression::Identifier(_) | Expression::Error(_) => {}
+        Expression::Binary(expr) => {
+            visitor.visit_expression_mut(&mut expr.left);
+            visitor.visit_expression_mut(&mut expr.right);
+        }
+        Expression::Unary(expr) => visitor.visit_expression_mut(&mut expr.operand),
+        Expression::Call(expr) => {
+            for argument in &mut expr.arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        Expression::IndirectCall(expr) => {
+            visitor.visit_expression_mut(&mut expr.callee);
+            for argument in &mut expr.arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        Expression::Assignment(expr) => visitor.visit_expression_mut(&mut expr.value),
+        Expression::Cast(expr) => visitor.visit_expression_mut(&mut expr.expression),
+    }
 }
 
 impl fmt::Display for Type {
@@ -191,6 +389,7 @@ impl fmt::Display for Type {
             Type::Int => write!(f, "int"),
             Type::Float => write!(f, "float"),
             Type::Bool => write!(f, "bool"),
+            Type::Char => write!(f, "char"),
             Type::String => write!(f, "string"),
             Type::Void => write!(f, "void"),
             Type::Function { parameters, return_type } => {
@@ -203,6 +402,7 @@ impl fmt::Display for Type {
                 }
                 write!(f, ") -> {}", return_type)
             }
+            Type::Inferred => write!(f, "_"),
         }
     }
 }
@@ -241,9 +441,12 @@ impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Literal::Integer(n) => write!(f, "{}", n),
+            // `1.0` e não `1`, para o texto continuar sendo um literal float
+            Literal::Float(x) if x.is_finite() && !format!("{}", x).contains('.') => write!(f, "{}.0", x),
             Literal::Float(x) => write!(f, "{}", x),
             Literal::Boolean(b) => write!(f, "{}", b),
-            Literal::String(s) => write!(f, "\"{}\"", s),
+            Literal::Char(c) => write!(f, "'{}'", c.escape_default()),
+            Literal::String(s) => write!(f, "\"{}\"", s.escape_default()),
         }
     }
 } 
\ No newline at end of file
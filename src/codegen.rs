@@ -1,67 +1,299 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use indexmap::{IndexMap, IndexSet};
 use crate::ast::*;
 use crate::error::{CompilerError, CompilerResult};
+use crate::semantic::{default_builtins, ExpressionTypes};
+use crate::utils::{CallingConvention, IntWidth};
+
+/// Rótulo da rotina que encerra o programa quando uma asserção falha
+const ASSERT_FAIL_LABEL: &str = "__assert_fail";
+
+/// Tamanho mínimo do quadro de uma função: espaço para 10 variáveis locais
+const MIN_FRAME_SIZE: i32 = 8 * 10;
+
+/// Marca o lugar do epílogo nos `return`; é substituída ao final da função,
+/// quando se sabe quais registradores precisam ser restaurados
+const EPILOGUE_PLACEHOLDER: &str = "    ; <epílogo>\n";
+
+/// Variável local: deslocamento relativo a rbp, tamanho em bytes (1, 4 ou 8)
+/// e tipo
+#[derive(Debug, Clone)]
+struct LocalVariable {
+    offset: i32,
+    size: i32,
+    ty: Type,
+}
+
+/// Rótulos de um laço em geração: `continue` salta para `start` e `break`
+/// para `end`
+#[derive(Debug, Clone)]
+pub(crate) struct LoopLabels {
+    pub(crate) name: Option<String>,
+    pub(crate) start: String,
+    pub(crate) end: String,
+}
+
+impl LoopLabels {
+    /// Laço alvo de um `break`/`continue`: o mais interno, ou o que tem o
+    /// rótulo pedido
+    pub(crate) fn find<'a>(loops: &'a [LoopLabels], label: Option<&str>) -> Option<&'a LoopLabels> {
+        match label {
+            Some(label) => loops.iter().rev().find(|target| target.name.as_deref() == Some(label)),
+            None => loops.last(),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct CodeGenerator {
-    _optimization_level: u8,
+    optimization_level: u8,
     label_counter: usize,
-    string_literals: HashMap<String, String>,
+    string_literals: IndexMap<String, String>,
     current_function: Option<String>,
-    local_variables: HashMap<String, i32>,
+    current_return_type: Option<Type>,
+    local_variables: HashMap<String, LocalVariable>,
+    global_variables: IndexMap<String, Option<i64>>,
+    global_sizes: HashMap<String, i32>,
+    global_types: HashMap<String, Type>,
+    global_initializers: String,
+    function_labels: HashSet<String>,
+    /// Tipos dos parâmetros das funções do programa e dos built-ins, para
+    /// converter argumentos int passados a parâmetros float
+    function_parameters: HashMap<String, Vec<Type>>,
+    /// Funções chamadas mas não definidas no programa (built-ins), declaradas
+    /// com `extern` para serem resolvidas na ligação
+    external_functions: IndexSet<String>,
+    uses_assert: bool,
+    /// Laços em geração, destino dos `break` e `continue`
+    loops: Vec<LoopLabels>,
+    /// Valores temporários empilhados pelas expressões em avaliação; usado
+    /// para manter rsp alinhado em 16 bytes nas chamadas
+    temporaries: usize,
+    /// Maior profundidade de `temporaries` na função em geração
+    max_temporaries: usize,
+    /// Maior espaço ocupado pelas variáveis locais da função em geração;
+    /// blocos irmãos reaproveitam o mesmo espaço
+    locals_size: i32,
+    /// Registradores preservados pelo chamado (callee-saved) que a função em
+    /// geração usa; são empilhados no prólogo e restaurados no epílogo
+    saved_registers: IndexSet<&'static str>,
     stack_offset: i32,
+    expression_types: ExpressionTypes,
+    int_width: IntWidth,
+    calling_convention: CallingConvention,
+    /// Linhas do código fonte, para comentar o assembly de cada comando
+    source_lines: Option<Vec<String>>,
+    /// Endereça dados relativos a rip (`[rel rótulo]`) para gerar código
+    /// independente de posição
+    position_independent: bool,
+    /// Função chamada por `_start`; sem ela, `_start` não é gerado
+    entry_point: Option<String>,
 }
 
 impl CodeGenerator {
     pub fn new(optimization_level: u8) -> Self {
         Self {
-            _optimization_level: optimization_level,
+            optimization_level,
             label_counter: 0,
-            string_literals: HashMap::new(),
+            string_literals: IndexMap::new(),
             current_function: None,
+            current_return_type: None,
             local_variables: HashMap::new(),
+            global_variables: IndexMap::new(),
+            global_sizes: HashMap::new(),
+            global_types: HashMap::new(),
+            global_initializers: String::new(),
+            function_labels: HashSet::new(),
+            function_parameters: HashMap::new(),
+            external_functions: IndexSet::new(),
+            uses_assert: false,
+            loops: Vec::new(),
+            temporaries: 0,
+            max_temporaries: 0,
+            locals_size: 0,
+            saved_registers: IndexSet::new(),
             stack_offset: 0,
+            expression_types: ExpressionTypes::new(),
+            int_width: IntWidth::default(),
+            calling_convention: CallingConvention::default(),
+            source_lines: None,
+            position_independent: false,
+            entry_point: Some("main".to_string()),
         }
     }
 
+    /// Define a largura dos inteiros no código gerado
+    pub fn with_int_width(mut self, int_width: IntWidth) -> Self {
+        self.int_width = int_width;
+        self
+    }
+
+    /// Define a convenção de chamada (System V ou Windows x64)
+    pub fn with_calling_convention(mut self, calling_convention: CallingConvention) -> Self {
+        self.calling_convention = calling_convention;
+        self
+    }
+
+    /// Usa os tipos resolvidos pela análise semântica na geração de código
+    pub fn with_expression_types(mut self, expression_types: ExpressionTypes) -> Self {
+        self.expression_types = expression_types;
+        self
+    }
+
+    /// Precede as instruções de cada comando com um comentário
+    /// `; linha N: <código>` tirado de `source`
+    pub fn with_source_annotations(mut self, source: &str) -> Self {
+        self.source_lines = Some(source.lines().map(|line| line.trim().to_string()).collect());
+        self
+    }
+
+    /// Gera código independente de posição (PIE): strings, globais e
+    /// endereços de funções são acessados relativos a rip, e as funções
+    /// externas são chamadas pela PLT
+    pub fn with_position_independent_code(mut self, enabled: bool) -> Self {
+        self.position_independent = enabled;
+        self
+    }
+
+    /// Define a função chamada por `_start` (por padrão `main`). Com `None`
+    /// o `_start` é omitido e as funções são exportadas com `global`, como
+    /// em uma biblioteca
+    pub fn with_entry_point(mut self, entry_point: Option<String>) -> Self {
+        self.entry_point = entry_point;
+        self
+    }
+
     pub fn generate(&mut self, program: &Program) -> CompilerResult<String> {
         let mut assembly = String::new();
 
+        for builtin in default_builtins() {
+            self.function_parameters.insert(builtin.name, builtin.parameters);
+        }
+
+        // Funções podem ser usadas como valores antes de sua definição
+        for statement in &program.statements {
+            if let Statement::Function(func) = statement {
+                self.function_labels.insert(func.name.clone());
+                let parameters = func.parameters.iter().map(|param| param.param_type.clone()).collect();
+                self.function_parameters.insert(func.name.clone(), parameters);
+            }
+        }
+
+        // Gerar código para cada declaração antes de emitir as seções,
+        // pois os literais de string são coletados durante a geração
+        let mut text = String::new();
+        for statement in &program.statements {
+            text.push_str(&self.generate_statement(statement)?);
+        }
+
         // Seção de dados
         assembly.push_str("section .data\n");
         for (string, label) in &self.string_literals {
-            assembly.push_str(&format!("{}: db \"{}\", 0\n", label, string));
+            assembly.push_str(&format!("{}: db {}, 0\n", label, Self::string_data(string)));
+        }
+        for (name, value) in &self.global_variables {
+            if let Some(value) = value {
+                let directive = match self.global_sizes.get(name) {
+                    Some(1) => "db",
+                    Some(4) => "dd",
+                    _ => "dq",
+                };
+                assembly.push_str(&format!("{}: {} {}\n", name, directive, value));
+            }
+        }
+
+        // Variáveis globais sem valor constante ficam na seção bss
+        if self.global_variables.values().any(|value| value.is_none()) {
+            assembly.push_str("\nsection .bss\n");
+            for (name, value) in &self.global_variables {
+                if value.is_none() {
+                    let directive = match self.global_sizes.get(name) {
+                        Some(1) => "resb",
+                        Some(4) => "resd",
+                        _ => "resq",
+                    };
+                    assembly.push_str(&format!("{}: {} 1\n", name, directive));
+                }
+            }
         }
 
         // Seção de texto
         assembly.push_str("\nsection .text\n");
-        assembly.push_str("global _start\n\n");
-
-        // Gerar código para cada declaração
-        for statement in &program.statements {
-            assembly.push_str(&self.generate_statement(statement)?);
+        match &self.entry_point {
+            Some(_) => assembly.push_str("global _start\n"),
+            None => {
+                // Sem _start não há onde avaliar os inicializadores de globais
+                if !self.global_initializers.is_empty() {
+                    return Err(CompilerError::codegen(
+                        "variáveis globais com inicializador não constante exigem um ponto de entrada",
+                    ));
+                }
+                for statement in &program.statements {
+                    if let Statement::Function(func) = statement {
+                        assembly.push_str(&format!("global {}\n", func.name));
+                    }
+                }
+            }
         }
+        for name in &self.external_functions {
+            assembly.push_str(&format!("extern {}\n", name));
+        }
+        assembly.push('\n');
+        assembly.push_str(&text);
 
-        // Adicionar função main se não existir
-        if !self.current_function.is_some() {
+        // Ponto de entrada: inicializa as globais e chama a função de entrada
+        if let (Some(entry_point), None) = (&self.entry_point, &self.current_function) {
             assembly.push_str("\n_start:\n");
-            assembly.push_str("    call main\n");
+            assembly.push_str(&self.global_initializers);
+            assembly.push_str(&format!("    call {}\n", entry_point));
             assembly.push_str("    mov rax, 60\n");
             assembly.push_str("    xor rdi, rdi\n");
             assembly.push_str("    syscall\n");
         }
 
+        // Destino das asserções que falham: exit(1)
+        if self.uses_assert {
+            assembly.push_str(&format!("\n{}:\n", ASSERT_FAIL_LABEL));
+            assembly.push_str("    mov rax, 60\n");
+            assembly.push_str("    mov rdi, 1\n");
+            assembly.push_str("    syscall\n");
+        }
+
         Ok(assembly)
     }
 
     fn generate_statement(&mut self, statement: &Statement) -> CompilerResult<String> {
+        let assembly = self.generate_statement_code(statement)?;
+        match self.source_annotation(statement) {
+            Some(annotation) if !assembly.is_empty() => Ok(annotation + &assembly),
+            _ => Ok(assembly),
+        }
+    }
+
+    /// Comentário com a linha do comando; blocos não são anotados, pois
+    /// seus comandos já são
+    fn source_annotation(&self, statement: &Statement) -> Option<String> {
+        if matches!(statement, Statement::Block(_) | Statement::Empty(_)) {
+            return None;
+        }
+        let line = statement.location().line;
+        let text = self.source_lines.as_ref()?.get(line.checked_sub(1)?)?;
+        Some(format!("    ; linha {}: {}\n", line, text))
+    }
+
+    fn generate_statement_code(&mut self, statement: &Statement) -> CompilerResult<String> {
         match statement {
             Statement::Expression(expr_stmt) => {
-                self.generate_expression(&expr_stmt.expression)?;
-                Ok("    pop rax\n".to_string())
+                let mut assembly = self.generate_expression(&expr_stmt.expression)?;
+                assembly.push_str("    pop rax\n");
+                Ok(assembly)
             }
             Statement::Declaration(decl_stmt) => {
-                self.generate_declaration(decl_stmt)
+                if self.current_function.is_none() {
+                    self.generate_global_declaration(decl_stmt)
+                } else {
+                    self.generate_declaration(decl_stmt)
+                }
             }
             Statement::Assignment(assign_stmt) => {
                 self.generate_assignment(assign_stmt)
@@ -78,6 +310,16 @@ impl CodeGenerator {
             Statement::Return(return_stmt) => {
                 self.generate_return_statement(return_stmt)
             }
+            Statement::Empty(_) => Ok(String::new()),
+            Statement::Error(_) => Err(CompilerError::codegen("o programa contém erros de sintaxe")),
+            Statement::Break(break_stmt) => match LoopLabels::find(&self.loops, break_stmt.label.as_deref()) {
+                Some(target) => Ok(format!("    jmp {}\n", target.end)),
+                None => Err(CompilerError::codegen("'break' fora de um laço")),
+            },
+            Statement::Continue(continue_stmt) => match LoopLabels::find(&self.loops, continue_stmt.label.as_deref()) {
+                Some(target) => Ok(format!("    jmp {}\n", target.start)),
+                None => Err(CompilerError::codegen("'continue' fora de um laço")),
+            },
             Statement::Block(block_stmt) => {
                 self.generate_block_statement(block_stmt)
             }
@@ -88,38 +330,78 @@ impl CodeGenerator {
         let mut assembly = String::new();
 
         // Alocar espaço na pilha para a variável
-        self.stack_offset -= 8;
-        let offset = self.stack_offset;
-        self.local_variables.insert(decl.name.clone(), offset);
+        let var_type = self.declaration_type(decl);
+        self.allocate_local(&decl.name, &var_type);
 
         // Se há inicializador, gerar código para ele
         if let Some(initializer) = &decl.initializer {
             assembly.push_str(&self.generate_expression(initializer)?);
             assembly.push_str("    pop rax\n");
-            assembly.push_str(&format!("    mov [rbp{}], rax\n", offset));
+            assembly.push_str(&self.promote_to_float(&var_type, initializer));
+            assembly.push_str(&self.store_variable(&decl.name)?);
         }
 
         Ok(assembly)
     }
 
+    fn generate_global_declaration(&mut self, decl: &DeclarationStatement) -> CompilerResult<String> {
+        // Inicializadores constantes vão direto para a seção de dados;
+        // os demais são avaliados em _start antes da chamada a main
+        let var_type = self.declaration_type(decl);
+        let value = decl.initializer.as_ref().and_then(|init| self.constant_value(init, &var_type));
+        self.global_variables.insert(decl.name.clone(), value);
+        self.global_sizes.insert(decl.name.clone(), self.type_size(&var_type));
+        self.global_types.insert(decl.name.clone(), var_type.clone());
+
+        if let (Some(initializer), None) = (&decl.initializer, value) {
+            let mut init = self.generate_expression(initializer)?;
+            init.push_str("    pop rax\n");
+            init.push_str(&self.promote_to_float(&var_type, initializer));
+            init.push_str(&self.store_variable(&decl.name)?);
+            self.global_initializers.push_str(&init);
+        }
+
+        Ok(String::new())
+    }
+
     fn generate_assignment(&mut self, assign: &AssignmentStatement) -> CompilerResult<String> {
         let mut assembly = String::new();
 
         // Gerar código para o valor
         assembly.push_str(&self.generate_expression(&assign.value)?);
         assembly.push_str("    pop rax\n");
-
-        // Encontrar offset da variável
-        let offset = self.local_variables.get(&assign.target).ok_or_else(|| {
-            CompilerError::codegen(format!("Variável '{}' não encontrada", assign.target))
-        })?;
-
-        assembly.push_str(&format!("    mov [rbp{}], rax\n", offset));
+        if let Some(target_type) = self.variable_type(&assign.target) {
+            assembly.push_str(&self.promote_to_float(target_type, &assign.value));
+        }
+        assembly.push_str(&self.store_variable(&assign.target)?);
 
         Ok(assembly)
     }
 
+    /// Valor de uma condição que é um literal booleano, para gerar só o
+    /// caminho tomado; a partir do nível de otimização 1
+    fn constant_condition(&self, condition: &Expression) -> Option<bool> {
+        if self.optimization_level == 0 {
+            return None;
+        }
+        match condition {
+            Expression::Literal(LiteralExpression { value: Literal::Boolean(value), .. }) => Some(*value),
+            _ => None,
+        }
+    }
+
     fn generate_if_statement(&mut self, if_stmt: &IfStatement) -> CompilerResult<String> {
+        match self.constant_condition(&if_stmt.condition) {
+            Some(true) => return self.generate_statement(&if_stmt.then_branch),
+            Some(false) => {
+                return match &if_stmt.else_branch {
+                    Some(else_branch) => self.generate_statement(else_branch),
+                    None => Ok(String::new()),
+                };
+            }
+            None => {}
+        }
+
         let mut assembly = String::new();
         let else_label = self.generate_label("else");
         let end_label = self.generate_label("endif");
@@ -146,20 +428,34 @@ impl CodeGenerator {
     }
 
     fn generate_while_statement(&mut self, while_stmt: &WhileStatement) -> CompilerResult<String> {
+        let constant_condition = self.constant_condition(&while_stmt.condition);
+        if constant_condition == Some(false) {
+            return Ok(String::new());
+        }
+
         let mut assembly = String::new();
         let loop_label = self.generate_label("while");
         let end_label = self.generate_label("endwhile");
 
         assembly.push_str(&format!("{}:\n", loop_label));
 
-        // Gerar código para a condição
-        assembly.push_str(&self.generate_expression(&while_stmt.condition)?);
-        assembly.push_str("    pop rax\n");
-        assembly.push_str("    cmp rax, 0\n");
-        assembly.push_str(&format!("    je {}\n", end_label));
+        // Gerar código para a condição; `while (true)` só sai por `break`
+        if constant_condition.is_none() {
+            assembly.push_str(&self.generate_expression(&while_stmt.condition)?);
+            assembly.push_str("    pop rax\n");
+            assembly.push_str("    cmp rax, 0\n");
+            assembly.push_str(&format!("    je {}\n", end_label));
+        }
 
         // Gerar código para o corpo do loop
-        assembly.push_str(&self.generate_statement(&while_stmt.body)?);
+        self.loops.push(LoopLabels {
+            name: while_stmt.label.clone(),
+            start: loop_label.clone(),
+            end: end_label.clone(),
+        });
+        let body = self.generate_statement(&while_stmt.body);
+        self.loops.pop();
+        assembly.push_str(&body?);
         assembly.push_str(&format!("    jmp {}\n", loop_label));
 
         assembly.push_str(&format!("{}:\n", end_label));
@@ -168,58 +464,112 @@ impl CodeGenerator {
     }
 
     fn generate_function(&mut self, func: &FunctionStatement) -> CompilerResult<String> {
+        // Como na análise semântica: o rótulo é global, mas o código ficaria
+        // no meio da função externa
+        if self.current_function.is_some() {
+            return Err(CompilerError::codegen(format!(
+                "funções aninhadas não são suportadas: '{}'",
+                func.name
+            )));
+        }
+
         let mut assembly = String::new();
 
         // Salvar estado anterior
         let old_function = self.current_function.take();
+        let old_return_type = self.current_return_type.take();
         let old_variables = std::mem::take(&mut self.local_variables);
         let old_stack_offset = self.stack_offset;
 
         self.current_function = Some(func.name.clone());
+        self.current_return_type = Some(func.return_type.clone());
         self.stack_offset = 0;
-
-        // Prologue da função
-        assembly.push_str(&format!("{}:\n", func.name));
-        assembly.push_str("    push rbp\n");
-        assembly.push_str("    mov rbp, rsp\n");
-
-        // Alocar espaço para variáveis locais
-        let local_size = 8 * 10; // Espaço para 10 variáveis locais
-        assembly.push_str(&format!("    sub rsp, {}\n", local_size));
-
-        // Salvar parâmetros em variáveis locais
-        for (i, param) in func.parameters.iter().enumerate() {
-            let offset = -(i as i32 + 1) * 8;
-            self.local_variables.insert(param.name.clone(), offset);
+        self.max_temporaries = 0;
+        self.locals_size = 0;
+        self.saved_registers.clear();
+
+        // Salvar parâmetros em variáveis locais: os primeiros chegam em
+        // registradores, os demais na pilha acima do endereço de retorno
+        // (e do shadow space, no Windows)
+        let registers = self.calling_convention.argument_registers();
+        let stack_base = 16 + self.calling_convention.shadow_space();
+        for (index, param) in func.parameters.iter().enumerate() {
+            self.allocate_local(&param.name, &param.param_type);
+            match registers.get(index) {
+                Some(register) => assembly.push_str(&format!("    mov rax, {}\n", register)),
+                None => {
+                    let offset = stack_base + (index - registers.len()) * 8;
+                    assembly.push_str(&format!("    mov rax, [rbp+{}]\n", offset));
+                }
+            }
+            assembly.push_str(&self.store_variable(&param.name)?);
         }
 
         // Gerar código para o corpo da função
         assembly.push_str(&self.generate_block_statement(&func.body)?);
 
-        // Epilogue da função
-        assembly.push_str("    mov rsp, rbp\n");
-        assembly.push_str("    pop rbp\n");
-        assembly.push_str("    ret\n\n");
+        // Epilogue da função: o `return;` implícito de funções void. Quando
+        // todos os caminhos já retornam, ele fica inalcançável, mas inofensivo
+        assembly.push_str(EPILOGUE_PLACEHOLDER);
+        assembly.push('\n');
+
+        // Prologue da função, gerado depois do corpo para saber quais
+        // registradores preservar. Eles ficam abaixo das variáveis locais,
+        // para não mudar seus deslocamentos
+        let local_size = self.frame_size();
+        let mut prologue = format!("{}:\n", func.name);
+        prologue.push_str("    push rbp\n");
+        prologue.push_str("    mov rbp, rsp\n");
+        prologue.push_str(&format!("    sub rsp, {}\n", local_size));
+        for register in &self.saved_registers {
+            prologue.push_str(&format!("    push {}\n", register));
+        }
+        // Manter rsp alinhado em 16 bytes
+        if self.saved_registers.len() % 2 == 1 {
+            prologue.push_str("    sub rsp, 8\n");
+        }
+
+        let mut epilogue = String::new();
+        if !self.saved_registers.is_empty() {
+            let saved_size = 8 * self.saved_registers.len() as i32;
+            epilogue.push_str(&format!("    lea rsp, [rbp-{}]\n", local_size + saved_size));
+            for register in self.saved_registers.iter().rev() {
+                epilogue.push_str(&format!("    pop {}\n", register));
+            }
+        }
+        epilogue.push_str("    mov rsp, rbp\n");
+        epilogue.push_str("    pop rbp\n");
+        epilogue.push_str("    ret\n");
+        let assembly = prologue + &assembly.replace(EPILOGUE_PLACEHOLDER, &epilogue);
 
         // Restaurar estado anterior
         self.current_function = old_function;
+        self.current_return_type = old_return_type;
         self.local_variables = old_variables;
         self.stack_offset = old_stack_offset;
 
         Ok(assembly)
     }
 
+    /// Blocos aninhados não ajustam `rsp` (seus locais ficam no quadro da
+    /// função), então o `return` usa sempre o epílogo da função, baseado em
+    /// `rbp`, qualquer que seja a profundidade
     fn generate_return_statement(&mut self, return_stmt: &ReturnStatement) -> CompilerResult<String> {
         let mut assembly = String::new();
 
         if let Some(value) = &return_stmt.value {
             assembly.push_str(&self.generate_expression(value)?);
             assembly.push_str("    pop rax\n");
+
+            // Funções float devolvem o resultado em xmm0 (e em rax, onde as
+            // chamadas do programa o leem); um int é convertido
+            if self.current_return_type == Some(Type::Float) {
+                assembly.push_str(&self.promote_to_float(&Type::Float, value));
+                assembly.push_str("    movq xmm0, rax\n");
+            }
         }
 
-        assembly.push_str("    mov rsp, rbp\n");
-        assembly.push_str("    pop rbp\n");
-        assembly.push_str("    ret\n");
+        assembly.push_str(EPILOGUE_PLACEHOLDER);
 
         Ok(assembly)
     }
@@ -227,15 +577,31 @@ impl CodeGenerator {
     fn generate_block_statement(&mut self, block: &BlockStatement) -> CompilerResult<String> {
         let mut assembly = String::new();
 
+        // Variáveis declaradas no bloco podem sombrear as externas; salvar o
+        // mapeamento para restaurá-lo ao sair do bloco
+        let outer_variables = self.local_variables.clone();
+        let outer_stack_offset = self.stack_offset;
+
         for statement in &block.statements {
             assembly.push_str(&self.generate_statement(statement)?);
         }
 
+        self.local_variables = outer_variables;
+        self.stack_offset = outer_stack_offset;
+
         Ok(assembly)
     }
 
     fn generate_expression(&mut self, expression: &Expression) -> CompilerResult<String> {
+        // Leituras de constantes e inicializadores de `const` já foram
+        // calculados pela análise semântica
+        if let Some(value) = self.expression_types.constant(expression) {
+            let value = value.clone();
+            return self.generate_literal(&value);
+        }
+
         match expression {
+            Expression::Error(_) => Err(CompilerError::codegen("o programa contém erros de sintaxe")),
             Expression::Literal(literal_expr) => {
                 self.generate_literal(&literal_expr.value)
             }
@@ -251,9 +617,15 @@ impl CodeGenerator {
             Expression::Call(call_expr) => {
                 self.generate_call_expression(call_expr)
             }
+            Expression::IndirectCall(call_expr) => {
+                self.generate_indirect_call_expression(call_expr)
+            }
             Expression::Assignment(assign_expr) => {
                 self.generate_assignment_expression(assign_expr)
             }
+            Expression::Cast(cast_expr) => {
+                self.generate_cast_expression(cast_expr)
+            }
         }
     }
 
@@ -263,95 +635,128 @@ impl CodeGenerator {
                 Ok(format!("    push {}\n", n))
             }
             Literal::Float(x) => {
-                // Para simplificar, tratamos float como int
-                Ok(format!("    push {}\n", *x as i64))
+                // Floats ficam na pilha com a representação IEEE 754 de 64 bits
+                Ok(format!("    mov rax, 0x{:016x}\n    push rax\n", x.to_bits()))
             }
             Literal::Boolean(b) => {
                 let value = if *b { 1 } else { 0 };
                 Ok(format!("    push {}\n", value))
             }
+            Literal::Char(c) => {
+                Ok(format!("    push {}\n", *c as u32))
+            }
             Literal::String(s) => {
                 let label = self.add_string_literal(s);
-                Ok(format!("    push {}\n", label))
+                if self.position_independent {
+                    Ok(format!("    lea rax, [rel {}]\n    push rax\n", label))
+                } else {
+                    Ok(format!("    push {}\n", label))
+                }
             }
         }
     }
 
     fn generate_identifier(&mut self, name: &str) -> CompilerResult<String> {
-        let offset = self.local_variables.get(name).ok_or_else(|| {
-            CompilerError::codegen(format!("Variável '{}' não encontrada", name))
-        })?;
+        // O nome de uma função usado como valor produz o seu endereço
+        if !self.is_variable(name) && self.function_labels.contains(name) {
+            if self.position_independent {
+                return Ok(format!("    lea rax, [rel {}]\n    push rax\n", name));
+            }
+            return Ok(format!("    mov rax, {}\n    push rax\n", name));
+        }
 
-        Ok(format!("    mov rax, [rbp{}]\n    push rax\n", offset))
+        Ok(format!("{}    push rax\n", self.load_variable(name)?))
     }
 
+    /// Os operandos são avaliados da esquerda para a direita (os efeitos
+    /// de chamadas acontecem nessa ordem) e a operação é feita com o
+    /// esquerdo em rax e o direito em rbx
     fn generate_binary_expression(&mut self, binary: &BinaryExpression) -> CompilerResult<String> {
         let mut assembly = String::new();
 
-        // Gerar código para o operando direito
-        assembly.push_str(&self.generate_expression(&binary.right)?);
         // Gerar código para o operando esquerdo
         assembly.push_str(&self.generate_expression(&binary.left)?);
+        // Gerar código para o operando direito
+        self.add_temporaries(1);
+        assembly.push_str(&self.generate_expression(&binary.right)?);
+        self.temporaries -= 1;
 
         // Carregar operandos
-        assembly.push_str("    pop rbx\n"); // Operando esquerdo
-        assembly.push_str("    pop rax\n"); // Operando direito
+        self.saved_registers.insert("rbx");
+        assembly.push_str("    pop rbx\n"); // Operando direito
+        assembly.push_str("    pop rax\n"); // Operando esquerdo
+
+        // Com algum operando float a operação é feita nos registradores SSE
+        let left_type = self.static_type(&binary.left);
+        let right_type = self.static_type(&binary.right);
+        if left_type == Some(Type::Float) || right_type == Some(Type::Float) {
+            assembly.push_str(&self.float_operand("xmm0", "rax", &binary.left));
+            assembly.push_str(&self.float_operand("xmm1", "rbx", &binary.right));
+            assembly.push_str(&Self::float_operation(&binary.operator)?);
+            assembly.push_str("    push rax\n");
+            return Ok(assembly);
+        }
+
+        // Registradores de 32 ou 64 bits conforme a largura dos inteiros
+        let narrow = self.is_narrow_expression(&binary.left);
+        let (ax, bx, dx) = if narrow { ("eax", "ebx", "edx") } else { ("rax", "rbx", "rdx") };
+        let sign_extend = if narrow { "    cdq\n" } else { "    cqo\n" };
 
         // Aplicar operação
         match &binary.operator {
             BinaryOperator::Add => {
-                assembly.push_str("    add rax, rbx\n");
+                assembly.push_str(&format!("    add {}, {}\n", ax, bx));
             }
             BinaryOperator::Subtract => {
-                assembly.push_str("    sub rax, rbx\n");
+                assembly.push_str(&format!("    sub {}, {}\n", ax, bx));
             }
             BinaryOperator::Multiply => {
-                assembly.push_str("    imul rax, rbx\n");
+                assembly.push_str(&format!("    imul {}, {}\n", ax, bx));
             }
             BinaryOperator::Divide => {
-                assembly.push_str("    cqo\n");
-                assembly.push_str("    idiv rbx\n");
+                assembly.push_str(sign_extend);
+                assembly.push_str(&format!("    idiv {}\n", bx));
             }
             BinaryOperator::Modulo => {
-                assembly.push_str("    cqo\n");
-                assembly.push_str("    idiv rbx\n");
-                assembly.push_str("    mov rax, rdx\n");
+                assembly.push_str(sign_extend);
+                assembly.push_str(&format!("    idiv {}\n", bx));
+                assembly.push_str(&format!("    mov {}, {}\n", ax, dx));
             }
             BinaryOperator::Equal => {
-                assembly.push_str("    cmp rax, rbx\n");
+                assembly.push_str(&format!("    cmp {}, {}\n", ax, bx));
                 assembly.push_str("    sete al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             BinaryOperator::NotEqual => {
-                assembly.push_str("    cmp rax, rbx\n");
+                assembly.push_str(&format!("    cmp {}, {}\n", ax, bx));
                 assembly.push_str("    setne al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             BinaryOperator::LessThan => {
-                assembly.push_str("    cmp rax, rbx\n");
+                assembly.push_str(&format!("    cmp {}, {}\n", ax, bx));
                 assembly.push_str("    setl al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             BinaryOperator::LessThanEqual => {
-                assembly.push_str("    cmp rax, rbx\n");
+                assembly.push_str(&format!("    cmp {}, {}\n", ax, bx));
                 assembly.push_str("    setle al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             BinaryOperator::GreaterThan => {
-                assembly.push_str("    cmp rax, rbx\n");
+                assembly.push_str(&format!("    cmp {}, {}\n", ax, bx));
                 assembly.push_str("    setg al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             BinaryOperator::GreaterThanEqual => {
-                assembly.push_str("    cmp rax, rbx\n");
+                assembly.push_str(&format!("    cmp {}, {}\n", ax, bx));
                 assembly.push_str("    setge al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             BinaryOperator::And => {
-                assembly.push_str("    and rax, rbx\n");
+                assembly.push_str(&format!("    and {}, {}\n", ax, bx));
             }
             BinaryOperator::Or => {
-                assembly.push_str("    or rax, rbx\n");
+                assembly.push_str(&format!("    or {}, {}\n", ax, bx));
             }
         }
 
@@ -367,18 +772,24 @@ impl CodeGenerator {
         assembly.push_str(&self.generate_expression(&unary.operand)?);
         assembly.push_str("    pop rax\n");
 
+        let ax = if self.is_narrow_expression(&unary.operand) { "eax" } else { "rax" };
+
         // Aplicar operação
         match &unary.operator {
+            UnaryOperator::Minus if self.static_type(&unary.operand) == Some(Type::Float) => {
+                // Negar um double é inverter o bit de sinal
+                assembly.push_str("    btc rax, 63\n");
+            }
             UnaryOperator::Minus => {
-                assembly.push_str("    neg rax\n");
+                assembly.push_str(&format!("    neg {}\n", ax));
             }
             UnaryOperator::Not => {
-                assembly.push_str("    cmp rax, 0\n");
+                assembly.push_str(&format!("    cmp {}, 0\n", ax));
                 assembly.push_str("    sete al\n");
-                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str(&format!("    movzx {}, al\n", ax));
             }
             UnaryOperator::Negate => {
-                assembly.push_str("    not rax\n");
+                assembly.push_str(&format!("    not {}\n", ax));
             }
         }
 
@@ -388,24 +799,186 @@ impl CodeGenerator {
     }
 
     fn generate_call_expression(&mut self, call: &CallExpression) -> CompilerResult<String> {
+        if call.function == "assert" && !self.is_variable(&call.function) {
+            return self.generate_assert(call);
+        }
+
+        let parameters = self.call_parameters(&call.function);
+        let (mut assembly, padded) = self.generate_arguments(&call.arguments, &parameters)?;
+
+        // Chamar a função; uma variável com tipo função é chamada pelo
+        // endereço que contém
+        if self.is_variable(&call.function) {
+            let address = self.variable_address(&call.function)?;
+            assembly.push_str(&format!("    mov rax, {}\n", address));
+            assembly.push_str(&self.call_sequence("rax", call.arguments.len(), padded));
+        } else if self.function_labels.contains(&call.function) {
+            assembly.push_str(&self.call_sequence(&call.function, call.arguments.len(), padded));
+        } else {
+            self.external_functions.insert(call.function.clone());
+            let target = if self.position_independent {
+                format!("{} wrt ..plt", call.function)
+            } else {
+                call.function.clone()
+            };
+            assembly.push_str(&self.call_sequence(&target, call.arguments.len(), padded));
+        }
+
+        // O resultado está em rax, empurrar para a pilha
+        assembly.push_str("    push rax\n");
+
+        Ok(assembly)
+    }
+
+    /// `assert(cond)` é gerado em linha: se a condição for falsa, salta
+    /// para a rotina que encerra o programa com código 1
+    fn generate_assert(&mut self, call: &CallExpression) -> CompilerResult<String> {
+        let [condition] = call.arguments.as_slice() else {
+            return Err(CompilerError::codegen("assert espera exatamente um argumento"));
+        };
+
+        self.uses_assert = true;
+
+        let mut assembly = self.generate_expression(condition)?;
+        assembly.push_str("    pop rax\n");
+        assembly.push_str("    cmp rax, 0\n");
+        assembly.push_str(&format!("    je {}\n", ASSERT_FAIL_LABEL));
+        assembly.push_str("    push rax\n");
+
+        Ok(assembly)
+    }
+
+    fn generate_indirect_call_expression(&mut self, call: &IndirectCallExpression) -> CompilerResult<String> {
+        let parameters = match self.static_type(&call.callee) {
+            Some(Type::Function { parameters, .. }) => parameters,
+            _ => Vec::new(),
+        };
+        let (mut assembly, padded) = self.generate_arguments(&call.arguments, &parameters)?;
+
+        // Avaliar a expressão chamada e chamar o endereço resultante
+        self.add_temporaries(call.arguments.len() + padded as usize);
+        assembly.push_str(&self.generate_expression(&call.callee)?);
+        self.temporaries -= call.arguments.len() + padded as usize;
+        assembly.push_str("    pop rax\n");
+        assembly.push_str(&self.call_sequence("rax", call.arguments.len(), padded));
+
+        assembly.push_str("    push rax\n");
+
+        Ok(assembly)
+    }
+
+    /// Empilha os argumentos em ordem reversa. Se a quantidade de valores
+    /// que ficará na pilha durante a chamada for ímpar, reserva antes 8 bytes
+    /// para que rsp esteja alinhado em 16 bytes no `call`, como exige a ABI;
+    /// retorna se esse espaço foi reservado. Argumentos int de parâmetros
+    /// float são convertidos.
+    fn generate_arguments(&mut self, arguments: &[Expression], parameters: &[Type]) -> CompilerResult<(String, bool)> {
         let mut assembly = String::new();
 
-        // Gerar código para os argumentos (em ordem reversa)
-        for arg in call.arguments.iter().rev() {
+        let stack_arguments = self.stack_argument_count(arguments.len());
+        let padded = (self.temporaries + stack_arguments) % 2 == 1;
+        if padded {
+            assembly.push_str("    sub rsp, 8\n");
+        }
+
+        let depth = self.temporaries;
+        self.add_temporaries(padded as usize);
+        for (index, arg) in arguments.iter().enumerate().rev() {
             assembly.push_str(&self.generate_expression(arg)?);
+            if let Some(parameter) = parameters.get(index) {
+                let promotion = self.promote_to_float(parameter, arg);
+                if !promotion.is_empty() {
+                    assembly.push_str(&format!("    pop rax\n{}    push rax\n", promotion));
+                }
+            }
+            self.add_temporaries(1);
         }
+        self.temporaries = depth;
+
+        Ok((assembly, padded))
+    }
+
+    /// Registra `count` valores a mais na pilha de operandos, acompanhando a
+    /// maior profundidade para o tamanho do quadro
+    fn add_temporaries(&mut self, count: usize) {
+        self.temporaries += count;
+        self.max_temporaries = self.max_temporaries.max(self.temporaries);
+    }
+
+    /// Bytes reservados no prólogo: as variáveis locais mais a maior pilha
+    /// de operandos (os temporários e o valor em cálculo), arredondados
+    /// para manter rsp alinhado em 16 bytes; no mínimo `MIN_FRAME_SIZE`
+    fn frame_size(&self) -> i32 {
+        let operands = 8 * (self.max_temporaries as i32 + 1);
+        let size = (self.locals_size + operands + 15) / 16 * 16;
+        size.max(MIN_FRAME_SIZE)
+    }
 
-        // Chamar a função
-        assembly.push_str(&format!("    call {}\n", call.function));
+    /// Quantos dos `arg_count` argumentos não cabem nos registradores da
+    /// convenção e são passados na pilha (a partir do sétimo, no System V)
+    fn stack_argument_count(&self, arg_count: usize) -> usize {
+        arg_count.saturating_sub(self.calling_convention.argument_registers().len())
+    }
 
-        // Limpar argumentos da pilha
-        let arg_count = call.arguments.len();
-        if arg_count > 0 {
-            assembly.push_str(&format!("    add rsp, {}\n", arg_count * 8));
+    /// Chama `target` com `arg_count` argumentos já empilhados (o primeiro no
+    /// topo): move os primeiros para os registradores da convenção, reserva o
+    /// shadow space e depois libera a pilha e o alinhamento
+    fn call_sequence(&self, target: &str, arg_count: usize, padded: bool) -> String {
+        let mut assembly = String::new();
+        let registers = self.calling_convention.argument_registers();
+
+        for register in registers.iter().take(arg_count) {
+            assembly.push_str(&format!("    pop {}\n", register));
         }
 
-        // O resultado está em rax, empurrar para a pilha
-        assembly.push_str("    push rax\n");
+        let shadow_space = self.calling_convention.shadow_space();
+        if shadow_space > 0 {
+            assembly.push_str(&format!("    sub rsp, {}\n", shadow_space));
+        }
+
+        assembly.push_str(&format!("    call {}\n", target));
+
+        // Limpar argumentos passados na pilha e o shadow space
+        let stack_bytes = self.stack_argument_count(arg_count) * 8 + shadow_space;
+        if stack_bytes > 0 {
+            assembly.push_str(&format!("    add rsp, {}\n", stack_bytes));
+        }
+        if padded {
+            assembly.push_str("    add rsp, 8\n");
+        }
+
+        assembly
+    }
+
+    fn generate_cast_expression(&mut self, cast: &CastExpression) -> CompilerResult<String> {
+        let mut assembly = self.generate_expression(&cast.expression)?;
+
+        let source_type = self.static_type(&cast.expression).ok_or_else(|| {
+            CompilerError::codegen("tipo de origem da conversão desconhecido; execute a análise semântica")
+        })?;
+
+        match (&source_type, &cast.target_type) {
+            (Type::Int, Type::Float) => {
+                let ax = if self.is_narrow_type(&Type::Int) { "eax" } else { "rax" };
+                assembly.push_str("    pop rax\n");
+                assembly.push_str(&format!("    cvtsi2sd xmm0, {}\n", ax));
+                assembly.push_str("    movq rax, xmm0\n");
+                assembly.push_str("    push rax\n");
+            }
+            (Type::Float, Type::Int) => {
+                assembly.push_str("    pop rax\n");
+                let ax = if self.is_narrow_type(&Type::Int) { "eax" } else { "rax" };
+                assembly.push_str("    movq xmm0, rax\n");
+                assembly.push_str(&format!("    cvttsd2si {}, xmm0\n", ax));
+                assembly.push_str("    push rax\n");
+            }
+            (Type::Bool, Type::Int) => {
+                assembly.push_str("    pop rax\n");
+                assembly.push_str("    movzx rax, al\n");
+                assembly.push_str("    push rax\n");
+            }
+            _ => {} // Mesmo tipo: nada a converter
+        }
 
         Ok(assembly)
     }
@@ -416,24 +989,290 @@ impl CodeGenerator {
         // Gerar código para o valor
         assembly.push_str(&self.generate_expression(&assign.value)?);
         assembly.push_str("    pop rax\n");
+        if let Some(target_type) = self.variable_type(&assign.target) {
+            assembly.push_str(&self.promote_to_float(target_type, &assign.value));
+        }
+        assembly.push_str(&self.store_variable(&assign.target)?);
+        assembly.push_str("    push rax\n");
 
-        // Encontrar offset da variável
-        let offset = self.local_variables.get(&assign.target).ok_or_else(|| {
-            CompilerError::codegen(format!("Variável '{}' não encontrada", assign.target))
-        })?;
+        Ok(assembly)
+    }
 
-        assembly.push_str(&format!("    mov [rbp{}], rax\n", offset));
-        assembly.push_str("    push rax\n");
+    /// Tipo resolvido de uma expressão, se a análise semântica o registrou
+    fn expression_type(&self, expression: &Expression) -> Option<&Type> {
+        self.expression_types.get(expression)
+    }
+
+    /// Tipo de uma expressão: o registrado pela análise semântica ou, na
+    /// falta dele, o de literais e conversões explícitas
+    fn static_type(&self, expression: &Expression) -> Option<Type> {
+        if let Some(ty) = self.expression_type(expression) {
+            return Some(ty.clone());
+        }
+
+        match expression {
+            Expression::Literal(literal) => Some(match literal.value {
+                Literal::Integer(_) => Type::Int,
+                Literal::Float(_) => Type::Float,
+                Literal::Boolean(_) => Type::Bool,
+                Literal::Char(_) => Type::Char,
+                Literal::String(_) => Type::String,
+            }),
+            Expression::Cast(cast) => Some(cast.target_type.clone()),
+            _ => None,
+        }
+    }
+
+    /// Verifica se a expressão produz um int
+    fn is_int_expression(&self, expression: &Expression) -> bool {
+        self.static_type(expression) == Some(Type::Int)
+    }
+
+    /// Converte para double o valor de `value` em rax quando ele é um int
+    /// usado onde se espera `target` float (declaração, atribuição,
+    /// argumento ou retorno)
+    fn promote_to_float(&self, target: &Type, value: &Expression) -> String {
+        if *target != Type::Float || !self.is_int_expression(value) {
+            return String::new();
+        }
+        let ax = if self.is_narrow_type(&Type::Int) { "eax" } else { "rax" };
+        format!("    cvtsi2sd xmm0, {}\n    movq rax, xmm0\n", ax)
+    }
+
+    /// Move o operando em `register` para `xmm`, convertendo-o se for int
+    fn float_operand(&self, xmm: &str, register: &str, operand: &Expression) -> String {
+        if !self.is_int_expression(operand) {
+            return format!("    movq {}, {}\n", xmm, register);
+        }
+        let register = if self.is_narrow_type(&Type::Int) {
+            register.replacen('r', "e", 1)
+        } else {
+            register.to_string()
+        };
+        format!("    cvtsi2sd {}, {}\n", xmm, register)
+    }
 
+    /// Operação entre doubles em xmm0 e xmm1, com o resultado em rax. As
+    /// comparações usam as condições sem sinal do `ucomisd` e são falsas
+    /// quando algum operando é NaN (exceto `!=`)
+    fn float_operation(operator: &BinaryOperator) -> CompilerResult<String> {
+        let arithmetic = |instruction: &str| format!("    {} xmm0, xmm1\n    movq rax, xmm0\n", instruction);
+        let comparison = |operands: &str, condition: &str| {
+            format!("    ucomisd {}\n    {} al\n    movzx rax, al\n", operands, condition)
+        };
+
+        let assembly = match operator {
+            BinaryOperator::Add => arithmetic("addsd"),
+            BinaryOperator::Subtract => arithmetic("subsd"),
+            BinaryOperator::Multiply => arithmetic("mulsd"),
+            BinaryOperator::Divide => arithmetic("divsd"),
+            // `a < b` é `b > a`: seta/setae já são falsas para NaN
+            BinaryOperator::GreaterThan => comparison("xmm0, xmm1", "seta"),
+            BinaryOperator::GreaterThanEqual => comparison("xmm0, xmm1", "setae"),
+            BinaryOperator::LessThan => comparison("xmm1, xmm0", "seta"),
+            BinaryOperator::LessThanEqual => comparison("xmm1, xmm0", "setae"),
+            // Operandos não ordenados (NaN) ligam a paridade
+            BinaryOperator::Equal => {
+                "    ucomisd xmm0, xmm1\n    sete al\n    setnp cl\n    and al, cl\n    movzx rax, al\n".to_string()
+            }
+            BinaryOperator::NotEqual => {
+                "    ucomisd xmm0, xmm1\n    setne al\n    setp cl\n    or al, cl\n    movzx rax, al\n".to_string()
+            }
+            // A análise semântica só aceita `%`, `&&` e `||` entre int e bool
+            BinaryOperator::Modulo | BinaryOperator::And | BinaryOperator::Or => {
+                return Err(CompilerError::codegen(format!("operação {} não suportada entre floats", operator)));
+            }
+        };
         Ok(assembly)
     }
 
+    /// Inteiros e booleanos ocupam 32 bits quando `IntWidth::Bits32` está ativo
+    fn is_narrow_type(&self, ty: &Type) -> bool {
+        self.int_width == IntWidth::Bits32 && matches!(ty, Type::Int | Type::Bool)
+    }
+
+    /// Operações sobre valores de tipo desconhecido são tratadas como inteiras
+    fn is_narrow_expression(&self, expression: &Expression) -> bool {
+        match self.static_type(expression) {
+            Some(ty) => self.is_narrow_type(&ty),
+            None => self.int_width == IntWidth::Bits32,
+        }
+    }
+
+    /// Tipo da variável declarada; em `var x = ...` é o tipo do
+    /// inicializador registrado pela análise semântica
+    fn declaration_type(&self, decl: &DeclarationStatement) -> Type {
+        match (&decl.var_type, &decl.initializer) {
+            (Type::Inferred, Some(initializer)) => {
+                self.expression_types.get(initializer).cloned().unwrap_or(Type::Int)
+            }
+            (Type::Inferred, None) => Type::Int,
+            (var_type, _) => var_type.clone(),
+        }
+    }
+
+    /// Tamanho em bytes de uma variável do tipo: chars ocupam 1 byte
+    fn type_size(&self, ty: &Type) -> i32 {
+        if *ty == Type::Char {
+            1
+        } else if self.is_narrow_type(ty) {
+            4
+        } else {
+            8
+        }
+    }
+
+    fn variable_type(&self, name: &str) -> Option<&Type> {
+        match self.local_variables.get(name) {
+            Some(local) => Some(&local.ty),
+            None => self.global_types.get(name),
+        }
+    }
+
+    /// Tipos dos parâmetros de uma chamada direta: os de uma variável com
+    /// tipo função, ou os da função do programa ou built-in
+    fn call_parameters(&self, function: &str) -> Vec<Type> {
+        if self.is_variable(function) {
+            return match self.variable_type(function) {
+                Some(Type::Function { parameters, .. }) => parameters.clone(),
+                _ => Vec::new(),
+            };
+        }
+        self.function_parameters.get(function).cloned().unwrap_or_default()
+    }
+
+    fn variable_size(&self, name: &str) -> i32 {
+        match self.local_variables.get(name) {
+            Some(local) => local.size,
+            None => self.global_sizes.get(name).copied().unwrap_or(8),
+        }
+    }
+
+    /// Reserva espaço na pilha para uma variável local
+    fn allocate_local(&mut self, name: &str, ty: &Type) {
+        let size = self.type_size(ty);
+        self.stack_offset -= size;
+        self.locals_size = self.locals_size.max(-self.stack_offset);
+        self.local_variables.insert(
+            name.to_string(),
+            LocalVariable { offset: self.stack_offset, size, ty: ty.clone() },
+        );
+    }
+
+    /// Carrega uma variável em rax (ou eax, se ocupar 32 bits; bytes são
+    /// estendidos com zeros)
+    fn load_variable(&self, name: &str) -> CompilerResult<String> {
+        let address = self.variable_address(name)?;
+        match self.variable_size(name) {
+            1 => Ok(format!("    movzx eax, byte {}\n", address)),
+            4 => Ok(format!("    mov eax, dword {}\n", address)),
+            _ => Ok(format!("    mov rax, {}\n", address)),
+        }
+    }
+
+    /// Grava rax (ou eax/al, conforme o tamanho da variável) em uma variável
+    fn store_variable(&self, name: &str) -> CompilerResult<String> {
+        let address = self.variable_address(name)?;
+        match self.variable_size(name) {
+            1 => Ok(format!("    mov byte {}, al\n", address)),
+            4 => Ok(format!("    mov dword {}, eax\n", address)),
+            _ => Ok(format!("    mov {}, rax\n", address)),
+        }
+    }
+
+    fn is_variable(&self, name: &str) -> bool {
+        self.local_variables.contains_key(name) || self.global_variables.contains_key(name)
+    }
+
+    /// Retorna o operando de memória de uma variável: local (relativa a rbp)
+    /// ou global (pelo seu rótulo)
+    fn variable_address(&self, name: &str) -> CompilerResult<String> {
+        if let Some(local) = self.local_variables.get(name) {
+            Ok(format!("[rbp{}]", local.offset))
+        } else if self.global_variables.contains_key(name) {
+            if self.position_independent {
+                Ok(format!("[rel {}]", name))
+            } else {
+                Ok(format!("[{}]", name))
+            }
+        } else {
+            Err(CompilerError::codegen(format!("Variável '{}' não encontrada", name)))
+        }
+    }
+
+    /// Valor de um inicializador de global do tipo `target` conhecido em
+    /// tempo de compilação: o de uma `const`, dobrado pela análise semântica,
+    /// ou um literal. Floats (e ints guardados em floats) ficam com a
+    /// representação IEEE 754
+    fn constant_value(&self, expression: &Expression, target: &Type) -> Option<i64> {
+        let value = match self.expression_types.constant(expression) {
+            Some(value) => value.clone(),
+            None => Self::literal_value(expression)?,
+        };
+        match value {
+            Literal::Integer(n) if *target == Type::Float => Some((n as f64).to_bits() as i64),
+            Literal::Integer(n) => Some(n),
+            Literal::Float(x) => Some(x.to_bits() as i64),
+            Literal::Boolean(b) => Some(b as i64),
+            Literal::Char(c) => Some(c as i64),
+            Literal::String(_) => None,
+        }
+    }
+
+    /// Literais inteiros (com sinal), booleanos e caracteres
+    fn literal_value(expression: &Expression) -> Option<Literal> {
+        match expression {
+            Expression::Literal(literal_expr) => match &literal_expr.value {
+                value @ (Literal::Integer(_) | Literal::Boolean(_) | Literal::Char(_)) => Some(value.clone()),
+                _ => None,
+            },
+            Expression::Unary(unary_expr) if unary_expr.operator == UnaryOperator::Minus => {
+                match Self::literal_value(&unary_expr.operand)? {
+                    Literal::Integer(n) => n.checked_neg().map(Literal::Integer),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Operandos de `db` para uma string: quebras de linha e aspas não podem
+    /// aparecer dentro de um literal do NASM e são emitidas como bytes
+    fn string_data(string: &str) -> String {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+
+        // Aspas e caracteres de controle (como '\n' e '\0') não podem
+        // ficar dentro das aspas do NASM e vão como bytes
+        for c in string.chars() {
+            match c {
+                '"' | '\0'..='\x1f' | '\x7f' => {
+                    if !current.is_empty() {
+                        parts.push(format!("\"{}\"", current));
+                        current.clear();
+                    }
+                    parts.push((c as u8).to_string());
+                }
+                _ => current.push(c),
+            }
+        }
+
+        if !current.is_empty() || parts.is_empty() {
+            parts.push(format!("\"{}\"", current));
+        }
+
+        parts.join(", ")
+    }
+
     fn generate_label(&mut self, prefix: &str) -> String {
         self.label_counter += 1;
         format!("{}_{}", prefix, self.label_counter)
     }
 
     fn add_string_literal(&mut self, string: &str) -> String {
+        if let Some(label) = self.string_literals.get(string) {
+            return label.clone();
+        }
         let label = format!("str_{}", self.string_literals.len());
         self.string_literals.insert(string.to_string(), label.clone());
         label
@@ -1,15 +1,21 @@
-use std::fmt;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+#[cfg(feature = "std")]
 use std::io;
+#[cfg(feature = "std")]
 use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum CompilerError {
+    #[cfg(feature = "std")]
     #[error("Erro ao ler arquivo {0}: {1}")]
-    FileReadError(PathBuf, io::Error),
+    FileReadError(PathBuf, #[source] io::Error),
 
+    #[cfg(feature = "std")]
     #[error("Erro ao escrever arquivo {0}: {1}")]
-    FileWriteError(PathBuf, io::Error),
+    FileWriteError(PathBuf, #[source] io::Error),
 
     #[error("Erro léxico na linha {line}, coluna {column}: {message}")]
     LexicalError {
@@ -25,14 +31,14 @@ pub enum CompilerError {
         message: String,
     },
 
-    #[error("Erro semântico: {message}")]
+    #[error("Erro semântico{}: {message}", location_suffix(.line, .column))]
     SemanticError {
         message: String,
         line: Option<usize>,
         column: Option<usize>,
     },
 
-    #[error("Erro de tipo: {message}")]
+    #[error("Erro de tipo{}: {message}", location_suffix(.line, .column))]
     TypeError {
         message: String,
         line: Option<usize>,
@@ -44,6 +50,20 @@ pub enum CompilerError {
         message: String,
     },
 
+    #[error("Erro do montador: {message}")]
+    AssemblerError {
+        message: String,
+    },
+
+    #[cfg(feature = "std")]
+    #[error("Erro ao processar a AST em JSON: {0}")]
+    JsonError(#[source] serde_json::Error),
+
+    #[error("Erro de configuração: {message}")]
+    ConfigError {
+        message: String,
+    },
+
     #[error("Erro interno do compilador: {message}")]
     InternalError {
         message: String,
@@ -114,12 +134,93 @@ impl CompilerError {
         }
     }
 
-    #[allow(dead_code)]
+    pub fn assembler(message: impl Into<String>) -> Self {
+        Self::AssemblerError {
+            message: message.into(),
+        }
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::ConfigError {
+            message: message.into(),
+        }
+    }
+
     pub fn internal(message: impl Into<String>) -> Self {
         Self::InternalError {
             message: message.into(),
         }
     }
+
+    /// Código estável da categoria do erro (ex.: `"E-TYPE-001"`), para que
+    /// ferramentas não dependam do texto da mensagem. Os códigos existentes
+    /// nunca mudam de significado; novas categorias recebem códigos novos.
+    pub fn code(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "std")]
+            CompilerError::FileReadError(..) => "E-IO-001",
+            #[cfg(feature = "std")]
+            CompilerError::FileWriteError(..) => "E-IO-002",
+            CompilerError::LexicalError { .. } => "E-LEX-001",
+            CompilerError::SyntaxError { .. } => "E-SYN-001",
+            CompilerError::SemanticError { .. } => "E-SEM-001",
+            CompilerError::TypeError { .. } => "E-TYPE-001",
+            CompilerError::CodeGenError { .. } => "E-GEN-001",
+            CompilerError::AssemblerError { .. } => "E-ASM-001",
+            #[cfg(feature = "std")]
+            CompilerError::JsonError(_) => "E-JSON-001",
+            CompilerError::ConfigError { .. } => "E-CFG-001",
+            CompilerError::InternalError { .. } => "E-INT-001",
+        }
+    }
+}
+
+/// Gravidade de um diagnóstico
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Erro ou aviso com a posição separada da mensagem, para ferramentas como
+/// editores que marcam o trecho do código
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl From<&CompilerError> for Diagnostic {
+    fn from(error: &CompilerError) -> Self {
+        let (message, line, column) = match error {
+            CompilerError::LexicalError { line, column, message }
+            | CompilerError::SyntaxError { line, column, message } => {
+                (message.clone(), Some(*line), Some(*column))
+            }
+            CompilerError::SemanticError { message, line, column }
+            | CompilerError::TypeError { message, line, column } => (message.clone(), *line, *column),
+            other => (other.to_string(), None, None),
+        };
+
+        Self {
+            severity: Severity::Error,
+            message,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "erro",
+            Severity::Warning => "aviso",
+        };
+        write!(f, "{}{}: {}", severity, location_suffix(&self.line, &self.column), self.message)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -135,6 +236,15 @@ impl fmt::Display for ErrorLocation {
     }
 }
 
+/// Texto " na linha L, coluna C" para erros cuja posição é opcional
+fn location_suffix(line: &Option<usize>, column: &Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!(" na linha {}, coluna {}", line, column),
+        (Some(line), None) => format!(" na linha {}", line),
+        _ => String::new(),
+    }
+}
+
 pub type CompilerResult<T> = Result<T, CompilerError>;
 
 impl From<String> for CompilerError {
@@ -1,27 +1,85 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
 use logos::Logos;
 use crate::ast::{Location, Literal};
 use crate::error::{CompilerError, CompilerResult};
 
+/// Tamanho máximo padrão, em bytes, de um identificador
+pub const DEFAULT_MAX_IDENTIFIER_LENGTH: usize = 1024;
+/// Tamanho máximo padrão, em bytes, do conteúdo de uma string (1 MiB)
+pub const DEFAULT_MAX_STRING_LENGTH: usize = 1024 * 1024;
+
+/// Configurações do analisador léxico
+#[derive(Debug, Clone)]
+pub struct LexerConfig {
+    /// Identificadores maiores que isso são um erro léxico
+    pub max_identifier_length: usize,
+    /// Strings com conteúdo maior que isso são um erro léxico
+    pub max_string_length: usize,
+    /// Reconhece `elif` como palavra-chave (o mesmo que `else if`); por
+    /// padrão é um identificador comum
+    pub elif_keyword: bool,
+}
+
+impl Default for LexerConfig {
+    fn default() -> Self {
+        Self {
+            max_identifier_length: DEFAULT_MAX_IDENTIFIER_LENGTH,
+            max_string_length: DEFAULT_MAX_STRING_LENGTH,
+            elif_keyword: false,
+        }
+    }
+}
+
+/// Estado do `logos` durante a análise: os limites e o motivo da rejeição
+/// do último token (limite excedido ou escape inválido), usado na mensagem
+/// de erro
+#[derive(Debug, Default)]
+pub struct LexerLimits {
+    config: LexerConfig,
+    exceeded: Option<(&'static str, usize)>,
+    /// Deslocamento do escape inválido dentro do token e a mensagem
+    invalid_escape: Option<(usize, String)>,
+}
+
+impl LexerLimits {
+    fn new(config: LexerConfig) -> Self {
+        Self { config, exceeded: None, invalid_escape: None }
+    }
+}
+
 #[derive(Logos, Debug, PartialEq, Clone)]
+#[logos(extras = LexerLimits)]
 pub enum Token {
     // Literais
     #[regex(r"[0-9]+", |lex| lex.slice().parse().unwrap_or(0))]
+    // Sufixo `i`, como em `3i`; outros sufixos são inválidos
+    #[regex(r"[0-9]+[a-zA-Z_][a-zA-Z0-9_]*", |lex| numeric_suffix(lex.slice(), "i"))]
     Integer(i64),
 
     #[regex(r"[0-9]+\.[0-9]+", |lex| lex.slice().parse().unwrap_or(0.0))]
+    // Sufixo `f`, como em `3f` ou `2.5f`
+    #[regex(r"[0-9]+f", |lex| numeric_suffix(lex.slice(), "f"), priority = 10)]
+    #[regex(r"[0-9]+\.[0-9]+[a-zA-Z_][a-zA-Z0-9_]*", |lex| numeric_suffix(lex.slice(), "f"))]
     Float(f64),
 
-    #[regex(r#""([^"]|\\")*""#, |lex| {
-        let s = lex.slice();
-        s[1..s.len()-1].to_string()
-    })]
+    #[regex(r#""([^"]|\\")*""#, |lex| string_literal(lex, 1))]
+    // String de várias linhas entre aspas triplas
+    #[regex(r#""""([^"]|"[^"]|""[^"])*""""#, |lex| string_literal(lex, 3))]
     String(String),
 
     #[regex(r"true|false", |lex| lex.slice().parse().unwrap_or(false))]
     Boolean(bool),
 
+    // Caractere ASCII entre aspas simples, com escapes como '\n'
+    #[regex(r"'([^'\\\n]|\\[^\n])'", |lex| char_literal(lex.slice()))]
+    Char(char),
+
     // Identificadores
-    #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*", |lex| lex.slice().to_string())]
+    #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*", identifier)]
     Identifier(String),
 
     // Operadores
@@ -29,6 +87,10 @@ pub enum Token {
     Plus,
     #[token("-")]
     Minus,
+    #[token("++")]
+    PlusPlus,
+    #[token("--")]
+    MinusMinus,
     #[token("*")]
     Star,
     #[token("/")]
@@ -81,22 +143,35 @@ pub enum Token {
     If,
     #[token("else")]
     Else,
+    // Produzido a partir do identificador `elif` quando
+    // `LexerConfig::elif_keyword` está ativo
+    Elif,
     #[token("while")]
     While,
     #[token("for")]
     For,
     #[token("return")]
     Return,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
     #[token("var")]
     Var,
+    #[token("const")]
+    Const,
     #[token("func")]
     Func,
+    #[token("as")]
+    As,
     #[token("int")]
     Int,
     #[token("float")]
     FloatType,
     #[token("bool")]
     Bool,
+    #[token("char")]
+    CharType,
     #[token("string")]
     StringType,
     #[token("void")]
@@ -106,6 +181,14 @@ pub enum Token {
     #[token("->")]
     Arrow,
 
+    // Diretiva `//line N "arquivo"`: renumera as linhas seguintes
+    #[regex(r#"//line[ \t]+[0-9]+([ \t]+"[^"\n]*")?[ \t]*"#, |lex| line_directive(lex.slice()))]
+    LineDirective((usize, Option<String>)),
+
+    // Comentários de documentação (`///`), anexados à função seguinte
+    #[regex(r"///[^\n]*", |lex| lex.slice()[3..].trim().to_string())]
+    DocComment(String),
+
     // Comentários e espaços em branco
     #[regex(r"//[^\n]*", logos::skip)]
     #[regex(r"/\*([^*]|\*+[^*/])*\*+/", logos::skip)]
@@ -124,6 +207,7 @@ impl Token {
             Token::Float(x) => Some(Literal::Float(*x)),
             Token::String(s) => Some(Literal::String(s.clone())),
             Token::Boolean(b) => Some(Literal::Boolean(*b)),
+            Token::Char(c) => Some(Literal::Char(*c)),
             _ => None,
         }
     }
@@ -132,9 +216,9 @@ impl Token {
     pub fn is_keyword(&self) -> bool {
         matches!(
             self,
-            Token::If | Token::Else | Token::While | Token::For | Token::Return |
-            Token::Var | Token::Func | Token::Int | Token::FloatType | Token::Bool |
-            Token::StringType | Token::Void
+            Token::If | Token::Else | Token::Elif | Token::While | Token::For | Token::Return |
+            Token::Break | Token::Continue | Token::Var | Token::Const | Token::Func | Token::Int | Token::FloatType | Token::Bool |
+            Token::CharType | Token::StringType | Token::Void
         )
     }
 
@@ -142,132 +226,518 @@ impl Token {
     pub fn is_type(&self) -> bool {
         matches!(
             self,
-            Token::Int | Token::FloatType | Token::Bool | Token::StringType | Token::Void
+            Token::Int | Token::FloatType | Token::Bool | Token::CharType | Token::StringType |
+            Token::Void
         )
     }
 
     // Token EOF será adicionado manualmente no lexer
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Display for Token {
+    /// Grafia do token no código fonte; tokens com valor usam uma descrição genérica
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Token::Integer(_) => "<int literal>",
+            Token::Float(_) => "<float literal>",
+            Token::String(_) => "<string literal>",
+            Token::Boolean(true) => "true",
+            Token::Boolean(false) => "false",
+            Token::Char(_) => "<char literal>",
+            Token::Identifier(_) => "<identifier>",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::PlusPlus => "++",
+            Token::MinusMinus => "--",
+            Token::Star => "*",
+            Token::Slash => "/",
+            Token::Percent => "%",
+            Token::Equal => "==",
+            Token::NotEqual => "!=",
+            Token::LessThan => "<",
+            Token::LessThanEqual => "<=",
+            Token::GreaterThan => ">",
+            Token::GreaterThanEqual => ">=",
+            Token::And => "&&",
+            Token::Or => "||",
+            Token::Not => "!",
+            Token::Assign => "=",
+            Token::LeftParen => "(",
+            Token::RightParen => ")",
+            Token::LeftBrace => "{",
+            Token::RightBrace => "}",
+            Token::LeftBracket => "[",
+            Token::RightBracket => "]",
+            Token::Semicolon => ";",
+            Token::Comma => ",",
+            Token::Dot => ".",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::Elif => "elif",
+            Token::While => "while",
+            Token::For => "for",
+            Token::Return => "return",
+            Token::Break => "break",
+            Token::Continue => "continue",
+            Token::Var => "var",
+            Token::Const => "const",
+            Token::Func => "func",
+            Token::As => "as",
+            Token::Int => "int",
+            Token::FloatType => "float",
+            Token::Bool => "bool",
+            Token::CharType => "char",
+            Token::StringType => "string",
+            Token::Void => "void",
+            Token::Colon => ":",
+            Token::Arrow => "->",
+            Token::LineDirective(_) => "//line",
+            Token::DocComment(_) => "///",
+            Token::Error => "<erro>",
+            Token::Eof => "<fim do arquivo>",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TokenInfo {
     pub token: Token,
     pub location: Location,
+    /// Texto exato do token no código fonte (vazio no `Eof`)
+    pub lexeme: String,
+}
+
+/// Iterador de tokens retornado por `Lexer::tokenize_streaming`
+pub struct TokenStream<'a> {
+    source: &'a str,
+    lexer: logos::Lexer<'a, Token>,
+    finished: bool,
+    line_delta: isize,
+    file: Option<String>,
+    /// Segunda metade de um `++`/`--` dividido em dois operadores
+    pending: Option<(TokenInfo, Range<usize>)>,
+}
+
+impl Iterator for TokenStream<'_> {
+    type Item = CompilerResult<TokenInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_spanned().map(|result| result.map(|(info, _)| info))
+    }
+}
+
+impl TokenStream<'_> {
+    /// Próximo token junto com o trecho em bytes que ele ocupa no código
+    fn next_spanned(&mut self) -> Option<CompilerResult<(TokenInfo, Range<usize>)>> {
+        if let Some(item) = self.pending.take() {
+            return Some(Ok(item));
+        }
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            return match self.lexer.next() {
+                Some(Err(_)) if self.lexer.extras.exceeded.is_some() => {
+                    self.finished = true;
+                    let (kind, limit) = self.lexer.extras.exceeded.take().unwrap_or_default();
+                    let span = self.lexer.span();
+                    let location = self.location(span.start, 0);
+                    Some(Err(CompilerError::lexical(
+                        location.line,
+                        location.column,
+                        format!("{} excede o limite de {} bytes", kind, limit),
+                    )))
+                }
+                Some(Err(_)) if self.lexer.extras.invalid_escape.is_some() => {
+                    self.finished = true;
+                    let (offset, message) = self.lexer.extras.invalid_escape.take().unwrap_or_default();
+                    let location = self.location(self.lexer.span().start + offset, 0);
+                    Some(Err(CompilerError::lexical(location.line, location.column, message)))
+                }
+                Some(Ok(Token::Error)) | Some(Err(_)) => {
+                    self.finished = true;
+                    let span = self.lexer.span();
+                    let slice = &self.source[span.start..span.end];
+                    let location = self.location(span.start, 0);
+                    Some(Err(CompilerError::lexical(
+                        location.line,
+                        location.column,
+                        format!("Token inválido: '{}'", slice),
+                    )))
+                }
+                Some(Ok(Token::LineDirective((line, file)))) => {
+                    // A linha seguinte à diretiva passa a ser a linha `line`
+                    let (physical_line, _) = line_column(self.source, self.lexer.span().start);
+                    self.line_delta = line as isize - (physical_line as isize + 1);
+                    if file.is_some() {
+                        self.file = file;
+                    }
+                    continue;
+                }
+                Some(Ok(token @ (Token::PlusPlus | Token::MinusMinus))) if !self.is_postfix_increment() => {
+                    // Fora de `i++`, como em `x--1` (`x - -1`), são dois operadores
+                    let span = self.lexer.span();
+                    let single = if token == Token::PlusPlus { Token::Plus } else { Token::Minus };
+                    let middle = span.start + 1;
+                    let second = TokenInfo {
+                        token: single.clone(),
+                        location: self.location(middle, 1),
+                        lexeme: self.source[middle..span.end].to_string(),
+                    };
+                    self.pending = Some((second, middle..span.end));
+                    let first = TokenInfo {
+                        token: single,
+                        location: self.location(span.start, 1),
+                        lexeme: self.source[span.start..middle].to_string(),
+                    };
+                    Some(Ok((first, span.start..middle)))
+                }
+                Some(Ok(token)) => {
+                    let token = match token {
+                        Token::Identifier(name) if name == "elif" && self.lexer.extras.config.elif_keyword => Token::Elif,
+                        token => token,
+                    };
+                    let span = self.lexer.span();
+                    let info = TokenInfo {
+                        token,
+                        location: self.location(span.start, span.end - span.start),
+                        lexeme: self.source[span.clone()].to_string(),
+                    };
+                    Some(Ok((info, span)))
+                }
+                None => {
+                    // Adicionar token EOF ao final
+                    self.finished = true;
+                    let end = self.source.len();
+                    let info = TokenInfo {
+                        token: Token::Eof,
+                        location: self.location(end, 0),
+                        lexeme: String::new(),
+                    };
+                    Some(Ok((info, end..end)))
+                }
+            };
+        }
+    }
+
+    /// Se o `++`/`--` atual é um incremento: vem colado a um nome (`i++`) e
+    /// não é seguido de um operando. Como depende do texto em volta,
+    /// `Lexer::relex` não reaproveita esses tokens junto a uma edição
+    fn is_postfix_increment(&self) -> bool {
+        let span = self.lexer.span();
+        let follows_name = self.source[..span.start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let operand_follows = self.source[span.end..]
+            .trim_start()
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '(' | '"' | '\'' | '!' | '+' | '-'));
+        follows_name && !operand_follows
+    }
+
+    /// Posição de um deslocamento, ajustada pelas diretivas `//line`
+    fn location(&self, offset: usize, length: usize) -> Location {
+        let (line, column) = line_column(self.source, offset);
+        Location {
+            line: (line as isize + self.line_delta).max(1) as usize,
+            column,
+            length,
+            file: self.file.clone(),
+        }
+    }
+}
+
+/// Tokens cuja divisão em `++`/`--` depende do texto vizinho
+fn is_increment_part(token: &Token) -> bool {
+    matches!(token, Token::Plus | Token::Minus | Token::PlusPlus | Token::MinusMinus)
+}
+
+/// Nome de um identificador; `None` (erro léxico) se exceder o limite, antes
+/// de copiar o texto
+fn identifier(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
+    let text = lex.slice();
+    let limit = lex.extras.config.max_identifier_length;
+    check_limit(&mut lex.extras, "Identificador", text.len(), limit)?;
+    Some(text.to_string())
+}
+
+/// Conteúdo de uma string sem as `quotes` aspas de cada lado, com os escapes
+/// decodificados; `None` (erro léxico) se exceder o limite, antes de copiar
+/// o texto, ou se tiver um escape inválido
+fn string_literal(lex: &mut logos::Lexer<'_, Token>, quotes: usize) -> Option<String> {
+    let text = lex.slice();
+    let content = &text[quotes..text.len() - quotes];
+    let limit = lex.extras.config.max_string_length;
+    check_limit(&mut lex.extras, "String", content.len(), limit)?;
+    match unescape(content) {
+        Ok(string) => Some(string),
+        Err((offset, message)) => {
+            lex.extras.invalid_escape = Some((quotes + offset, message));
+            None
+        }
+    }
+}
+
+/// Decodifica os escapes de uma string: os simples (`\n`, `\t`, `\r`, `\0`,
+/// `\\`, `\"`, `\'`), `\xNN` (ASCII, até `\x7F`) e `\u{N}` (qualquer valor
+/// escalar Unicode, com 1 a 6 dígitos). Em caso de erro, retorna o
+/// deslocamento da barra do escape e a mensagem
+fn unescape(content: &str) -> Result<String, (usize, String)> {
+    let mut string = String::with_capacity(content.len());
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        if c != '\\' {
+            string.push(c);
+            continue;
+        }
+
+        let escape = match chars.next() {
+            Some((_, 'n')) => '\n',
+            Some((_, 't')) => '\t',
+            Some((_, 'r')) => '\r',
+            Some((_, '0')) => '\0',
+            Some((_, '\\')) => '\\',
+            Some((_, '"')) => '"',
+            Some((_, '\'')) => '\'',
+            Some((_, 'x')) => {
+                let mut digits = String::new();
+                while let (true, Some((_, c))) = (digits.len() < 2, chars.next_if(|(_, c)| c.is_ascii_hexdigit())) {
+                    digits.push(c);
+                }
+                if digits.len() != 2 {
+                    return Err((offset, "Escape \\x exige dois dígitos hexadecimais".to_string()));
+                }
+                let value = u8::from_str_radix(&digits, 16).unwrap_or_default();
+                if !value.is_ascii() {
+                    return Err((offset, format!("Escape \\x{} fora do intervalo ASCII (máximo \\x7F)", digits)));
+                }
+                value as char
+            }
+            Some((_, 'u')) => {
+                let malformed = || (offset, "Escape \\u malformado: use \\u{...} com 1 a 6 dígitos hexadecimais".to_string());
+                if chars.next_if(|&(_, c)| c == '{').is_none() {
+                    return Err(malformed());
+                }
+                let mut digits = String::new();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                    digits.push(c);
+                }
+                if chars.next_if(|&(_, c)| c == '}').is_none() || digits.is_empty() || digits.len() > 6 {
+                    return Err(malformed());
+                }
+                let value = u32::from_str_radix(&digits, 16).unwrap_or_default();
+                char::from_u32(value).ok_or_else(|| {
+                    (offset, format!("Escape \\u{{{}}} não é um valor escalar Unicode válido", digits))
+                })?
+            }
+            Some((_, other)) => return Err((offset, format!("Escape desconhecido: '\\{}'", other))),
+            None => return Err((offset, "Escape incompleto no fim da string".to_string())),
+        };
+        string.push(escape);
+    }
+
+    Ok(string)
+}
+
+fn check_limit(limits: &mut LexerLimits, kind: &'static str, length: usize, limit: usize) -> Option<()> {
+    if length > limit {
+        limits.exceeded = Some((kind, limit));
+        return None;
+    }
+    Some(())
+}
+
+/// Valor de um literal numérico com sufixo de tipo; `None` (erro léxico) se
+/// o sufixo não for `suffix`
+fn numeric_suffix<T: core::str::FromStr>(text: &str, suffix: &str) -> Option<T> {
+    text.strip_suffix(suffix)?.parse().ok()
+}
+
+/// Valor de um literal como `'a'` ou `'\n'`; só aceita caracteres ASCII
+fn char_literal(text: &str) -> Option<char> {
+    let inner = &text[1..text.len() - 1];
+    let c = match inner.strip_prefix('\\') {
+        Some("n") => '\n',
+        Some("t") => '\t',
+        Some("r") => '\r',
+        Some("0") => '\0',
+        Some("\\") => '\\',
+        Some("'") => '\'',
+        Some("\"") => '"',
+        Some(_) => return None,
+        None => inner.chars().next()?,
+    };
+    c.is_ascii().then_some(c)
+}
+
+/// Interpreta `//line N` ou `//line N "arquivo"`
+fn line_directive(text: &str) -> Option<(usize, Option<String>)> {
+    let rest = text.strip_prefix("//line")?.trim();
+    let (number, file) = match rest.split_once(char::is_whitespace) {
+        Some((number, file)) => (number, Some(file.trim().trim_matches('"').to_string())),
+        None => (rest, None),
+    };
+    Some((number.parse().ok()?, file))
+}
+
+/// Calcula linha e coluna (a partir de 1) de um deslocamento em bytes
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.chars().filter(|&c| c == '\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(idx) => before.len() - idx,
+        None => before.len() + 1,
+    };
+    (line, column)
 }
 
 pub struct Lexer {
     source: String,
+    config: LexerConfig,
     tokens: Vec<TokenInfo>,
+    /// Trecho em bytes de cada token de `tokens`, usado por `relex`
+    spans: Vec<Range<usize>>,
     _current_pos: usize,
 }
 
 impl Lexer {
     pub fn new(source: &str) -> Self {
+        Self::with_config(source, LexerConfig::default())
+    }
+
+    pub fn with_config(source: &str, config: LexerConfig) -> Self {
         Self {
             source: source.to_string(),
+            config,
             tokens: Vec::new(),
+            spans: Vec::new(),
             _current_pos: 0,
         }
     }
 
     pub fn tokenize(&mut self) -> CompilerResult<Vec<TokenInfo>> {
-        let mut lexer = Token::lexer(&self.source);
+        self.tokens.clear();
+        self.spans.clear();
+
+        let mut stream = self.tokenize_streaming();
         let mut tokens = Vec::new();
-        let source = &self.source;
-
-        while let Some(token) = lexer.next() {
-            match token {
-                Ok(Token::Error) => {
-                    let span = lexer.span();
-                    let slice = &source[span.start..span.end];
-                    // Calcular linha e coluna do início do token
-                    let (line, column) = {
-                        let before = &source[..span.start];
-                        let line = before.chars().filter(|&c| c == '\n').count() + 1;
-                        let last_newline = before.rfind('\n');
-                        let column = match last_newline {
-                            Some(idx) => before.len() - idx,
-                            None => before.len() + 1,
-                        };
-                        (line, column)
-                    };
-                    return Err(CompilerError::lexical(
-                        line,
-                        column,
-                        format!("Token inválido: '{}'", slice),
-                    ));
-                }
-                Ok(token) => {
-                    let span = lexer.span();
-                    let slice = &source[span.start..span.end];
-                    // Calcular linha e coluna do início do token
-                    let (line, column) = {
-                        let before = &source[..span.start];
-                        let line = before.chars().filter(|&c| c == '\n').count() + 1;
-                        let last_newline = before.rfind('\n');
-                        let column = match last_newline {
-                            Some(idx) => before.len() - idx,
-                            None => before.len() + 1,
-                        };
-                        (line, column)
-                    };
-                    let length = slice.len();
-                    let location = Location {
-                        line,
-                        column,
-                        length,
-                    };
+        let mut spans = Vec::new();
+        while let Some(result) = stream.next_spanned() {
+            let (info, span) = result?;
+            tokens.push(info);
+            spans.push(span);
+        }
 
-                    tokens.push(TokenInfo {
-                        token,
-                        location,
-                    });
+        self.tokens = tokens.clone();
+        self.spans = spans;
+        Ok(tokens)
+    }
+
+    /// Substitui os bytes `range` do código por `replacement` e atualiza os
+    /// tokens da última chamada a `tokenize` (ou `relex`) sem reanalisar o
+    /// arquivo inteiro: a análise recomeça um token antes da edição e para
+    /// assim que reencontra o início de um token antigo; os tokens seguintes
+    /// são reaproveitados com a posição ajustada. Sem tokens anteriores, ou
+    /// com diretivas `//line`, o código é reanalisado por completo.
+    ///
+    /// Entra em pânico se `range` não estiver dentro do código ou não cair em
+    /// limites de caracteres, como `String::replace_range`.
+    pub fn relex(&mut self, range: Range<usize>, replacement: &str) -> CompilerResult<Vec<TokenInfo>> {
+        let old_end = line_column(&self.source, range.end);
+        let had_directives = self.source.contains("//line");
+        self.source.replace_range(range.clone(), replacement);
+
+        if self.tokens.is_empty() || had_directives || self.source.contains("//line") {
+            return self.tokenize();
+        }
+
+        let new_edit_end = range.start + replacement.len();
+        let new_end = line_column(&self.source, new_edit_end);
+
+        // O token que encosta na edição pode se juntar ao texto novo, e o
+        // anterior a ele também (como `1` `.` seguido de `5`)
+        let touched = self.spans.iter().position(|span| span.end >= range.start).unwrap_or(self.spans.len() - 1);
+        let mut restart = touched.saturating_sub(1);
+        // `+`/`-` podem ser metade de um `++`/`--` dividido: recomeçar antes
+        // de toda a sequência
+        while restart > 0 && is_increment_part(&self.tokens[restart].token) {
+            restart -= 1;
+        }
+
+        let mut lexer = Token::lexer_with_extras(&self.source, LexerLimits::new(self.config.clone()));
+        lexer.bump(self.spans[restart].start);
+        let mut stream = TokenStream {
+            source: &self.source,
+            lexer,
+            finished: false,
+            line_delta: 0,
+            file: None,
+            pending: None,
+        };
+
+        let mut tokens = Vec::new();
+        let mut spans = Vec::new();
+        let resume = loop {
+            let (info, span) = match stream.next_spanned() {
+                Some(Ok(item)) => item,
+                Some(Err(error)) => {
+                    self.tokens.clear();
+                    self.spans.clear();
+                    return Err(error);
                 }
-                Err(_) => {
-                    let span = lexer.span();
-                    let slice = &source[span.start..span.end];
-                    let (line, column) = {
-                        let before = &source[..span.start];
-                        let line = before.chars().filter(|&c| c == '\n').count() + 1;
-                        let last_newline = before.rfind('\n');
-                        let column = match last_newline {
-                            Some(idx) => before.len() - idx,
-                            None => before.len() + 1,
-                        };
-                        (line, column)
-                    };
-                    return Err(CompilerError::lexical(
-                        line,
-                        column,
-                        format!("Token inválido: '{}'", slice),
-                    ));
+                None => break self.tokens.len(),
+            };
+
+            // Depois da edição, o código a partir de um início de token
+            // antigo é idêntico: dali em diante os tokens são os mesmos. Um
+            // `++`/`--` ainda depende do caractere antes dele, que pode ser
+            // a edição
+            if span.start >= new_edit_end && !is_increment_part(&info.token) {
+                let old_start = span.start - new_edit_end + range.end;
+                if let Ok(index) = self.spans[touched..].binary_search_by_key(&old_start, |span| span.start) {
+                    break touched + index;
                 }
             }
-        }
 
-        // Adicionar token EOF ao final
-        // Calcular linha e coluna do final do arquivo
-        let (line, column) = {
-            let before = &self.source;
-            let line = before.chars().filter(|&c| c == '\n').count() + 1;
-            let last_newline = before.rfind('\n');
-            let column = match last_newline {
-                Some(idx) => before.len() - idx,
-                None => before.len() + 1,
-            };
-            (line, column)
+            tokens.push(info);
+            spans.push(span);
         };
-        tokens.push(TokenInfo {
-            token: Token::Eof,
-            location: Location {
-                line,
-                column,
-                length: 0,
-            },
-        });
 
-        self.tokens = tokens.clone();
-        Ok(tokens)
+        // Reaproveitar os tokens após a edição, deslocando as posições
+        let byte_delta = new_edit_end as isize - range.end as isize;
+        let line_delta = new_end.0 as isize - old_end.0 as isize;
+        for (mut info, span) in self.tokens.drain(resume..).zip(self.spans.drain(resume..)) {
+            if info.location.line == old_end.0 {
+                info.location.column = info.location.column + new_end.1 - old_end.1;
+            }
+            info.location.line = (info.location.line as isize + line_delta) as usize;
+            tokens.push(info);
+            spans.push((span.start as isize + byte_delta) as usize..(span.end as isize + byte_delta) as usize);
+        }
+
+        self.tokens.truncate(restart);
+        self.spans.truncate(restart);
+        self.tokens.extend(tokens);
+        self.spans.extend(spans);
+        Ok(self.tokens.clone())
+    }
+
+    /// Produz os tokens sob demanda, um de cada vez, terminando no `Eof`.
+    /// Após um erro léxico o iterador não produz mais itens.
+    pub fn tokenize_streaming(&self) -> TokenStream<'_> {
+        TokenStream {
+            source: &self.source,
+            lexer: Token::lexer_with_extras(&self.source, LexerLimits::new(self.config.clone())),
+            finished: false,
+            line_delta: 0,
+            file: None,
+            pending: None,
+        }
     }
 
     #[allow(dead_code)]
@@ -292,25 +762,31 @@ impl Lexer {
     #[allow(dead_code)]
     pub fn expect(&mut self, expected: Token) -> CompilerResult<&TokenInfo> {
         if let Some(token_info) = self.current() {
-            let token_discriminant = std::mem::discriminant(&token_info.token);
-            let expected_discriminant = std::mem::discriminant(&expected);
+            let token_discriminant = core::mem::discriminant(&token_info.token);
+            let expected_discriminant = core::mem::discriminant(&expected);
             
             if token_discriminant == expected_discriminant {
                 let _token_info = token_info.clone();
                 self.advance();
                 Ok(&self.tokens[self._current_pos - 1])
+            } else if token_info.token == Token::Eof {
+                Err(CompilerError::syntax(
+                    token_info.location.line,
+                    token_info.location.column,
+                    format!("Esperado '{}', mas chegou ao fim do arquivo", expected),
+                ))
             } else {
                 Err(CompilerError::syntax(
                     token_info.location.line,
                     token_info.location.column,
-                    format!("Esperado '{:?}', encontrado '{:?}'", expected, token_info.token),
+                    format!("Esperado '{}', encontrado '{}'", expected, token_info.lexeme),
                 ))
             }
         } else {
             Err(CompilerError::syntax(
                 0,
                 0,
-                format!("Esperado '{:?}', mas chegou ao fim do arquivo", expected),
+                format!("Esperado '{}', mas chegou ao fim do arquivo", expected),
             ))
         }
     }
@@ -318,7 +794,7 @@ impl Lexer {
     #[allow(dead_code)]
     pub fn check(&self, token: Token) -> bool {
         if let Some(token_info) = self.current() {
-            std::mem::discriminant(&token_info.token) == std::mem::discriminant(&token)
+            core::mem::discriminant(&token_info.token) == core::mem::discriminant(&token)
         } else {
             false
         }
@@ -345,7 +821,7 @@ mod tests {
         let mut lexer = Lexer::new(source);
         let tokens = lexer.tokenize().unwrap();
 
-        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens.len(), 6);
         assert!(matches!(tokens[0].token, Token::Integer(123)));
         assert!(matches!(tokens[1].token, Token::Float(45.67)));
         assert!(matches!(tokens[2].token, Token::Boolean(true)));
@@ -373,13 +849,48 @@ mod tests {
         assert!(matches!(tokens[10].token, Token::GreaterThanEqual));
     }
 
+    #[test]
+    fn test_streaming_matches_tokenize() {
+        let source = "func main() -> int {\n    var x: int = 1 + 2;\n    return x;\n}";
+        let mut lexer = Lexer::new(source);
+        let streamed: Vec<TokenInfo> = lexer
+            .tokenize_streaming()
+            .collect::<CompilerResult<_>>()
+            .unwrap();
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(streamed, tokens);
+    }
+
+    #[test]
+    fn test_streaming_stops_after_eof() {
+        let lexer = Lexer::new("x y");
+        let mut stream = lexer.tokenize_streaming();
+
+        assert!(matches!(stream.next(), Some(Ok(TokenInfo { token: Token::Identifier(_), .. }))));
+        assert!(matches!(stream.next(), Some(Ok(TokenInfo { token: Token::Identifier(_), .. }))));
+        assert!(matches!(stream.next(), Some(Ok(TokenInfo { token: Token::Eof, .. }))));
+        assert!(stream.next().is_none());
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_streaming_stops_after_error() {
+        let lexer = Lexer::new("x @ y");
+        let mut stream = lexer.tokenize_streaming();
+
+        assert!(matches!(stream.next(), Some(Ok(_))));
+        assert!(matches!(stream.next(), Some(Err(CompilerError::LexicalError { .. }))));
+        assert!(stream.next().is_none());
+    }
+
     #[test]
     fn test_keywords() {
         let source = "if else while for return var func";
         let mut lexer = Lexer::new(source);
         let tokens = lexer.tokenize().unwrap();
 
-        assert_eq!(tokens.len(), 7);
+        assert_eq!(tokens.len(), 8);
         assert!(matches!(tokens[0].token, Token::If));
         assert!(matches!(tokens[1].token, Token::Else));
         assert!(matches!(tokens[2].token, Token::While));
@@ -388,4 +899,235 @@ mod tests {
         assert!(matches!(tokens[5].token, Token::Var));
         assert!(matches!(tokens[6].token, Token::Func));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_doc_comments() {
+        let source = "/// Soma dois números\n// comentário comum\nfunc";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0].token, Token::DocComment("Soma dois números".to_string()));
+        assert!(matches!(tokens[1].token, Token::Func));
+    }
+
+    #[test]
+    fn test_line_directive() {
+        let source = "x\n//line 100 \"original.rc\"\ny\nz\n// comentário comum\nw";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[0].location.line, 1);
+        assert_eq!(tokens[0].location.file, None);
+        assert_eq!(tokens[1].token, Token::Identifier("y".to_string()));
+        assert_eq!(tokens[1].location.line, 100);
+        assert_eq!(tokens[1].location.file.as_deref(), Some("original.rc"));
+        assert_eq!(tokens[2].location.line, 101);
+        assert_eq!(tokens[3].location.line, 103);
+    }
+
+    #[test]
+    fn test_multiline_string() {
+        let source = "var s = \"\"\"primeira\nsegunda\"\"\";\nx";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens.len(), 7);
+        assert_eq!(tokens[3].token, Token::String("primeira\nsegunda".to_string()));
+        assert_eq!(tokens[3].location.line, 1);
+        assert_eq!(tokens[4].token, Token::Semicolon);
+        assert_eq!((tokens[4].location.line, tokens[4].location.column), (2, 11));
+        assert_eq!(tokens[5].location.line, 3);
+    }
+
+    #[test]
+    fn test_char_literals() {
+        let mut lexer = Lexer::new("'a' '\\n' '\\'' char");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token, Token::Char('a'));
+        assert_eq!(tokens[1].token, Token::Char('\n'));
+        assert_eq!(tokens[2].token, Token::Char('\''));
+        assert_eq!(tokens[3].token, Token::CharType);
+    }
+
+    #[test]
+    fn test_unterminated_char_literal() {
+        let mut lexer = Lexer::new("var c: char = 'a;");
+        let error = lexer.tokenize().unwrap_err();
+
+        assert!(matches!(error, CompilerError::LexicalError { line: 1, column: 15, .. }));
+    }
+
+    #[test]
+    fn test_numeric_suffixes() {
+        let mut lexer = Lexer::new("3i 3f 2.5f 42");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token, Token::Integer(3));
+        assert_eq!(tokens[1].token, Token::Float(3.0));
+        assert_eq!(tokens[2].token, Token::Float(2.5));
+        assert_eq!(tokens[3].token, Token::Integer(42));
+
+        for source in ["3z", "2.5i", "3fx"] {
+            let mut lexer = Lexer::new(source);
+            assert!(matches!(lexer.tokenize(), Err(CompilerError::LexicalError { .. })), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_relex_matches_full_tokenize() {
+        let source = "func main() -> int {\n    var total: int = 1 + 2;\n    return total;\n}\n";
+        let edits: [(&str, &str); 6] = [
+            ("total: int = 1", "total: int = 42"),
+            ("1 + 2;\n", "1 + 2;\n    total = 3;\n"),
+            ("2;\n    return", "2; return"),
+            ("1 + 2", "1. + 2"),
+            ("main", "main // comentário\n"),
+            ("-> int {", "-> in"),
+        ];
+
+        for (old, new) in edits {
+            let start = source.find(old).unwrap();
+            let mut edited = source.to_string();
+            edited.replace_range(start..start + old.len(), new);
+
+            let mut lexer = Lexer::new(source);
+            lexer.tokenize().unwrap();
+            let relexed = lexer.relex(start..start + old.len(), new).unwrap();
+
+            assert_eq!(relexed, Lexer::new(&edited).tokenize().unwrap(), "edição {:?} -> {:?}", old, new);
+        }
+
+        // Edições seguidas reaproveitam os trechos já atualizados
+        let mut lexer = Lexer::new("var x: int = 1;\nvar y: int = 2;");
+        lexer.tokenize().unwrap();
+        lexer.relex(13..14, "10").unwrap();
+        let relexed = lexer.relex(30..31, "3.5").unwrap();
+        let expected = Lexer::new("var x: int = 10;\nvar y: int = 3.5;").tokenize().unwrap();
+        assert_eq!(relexed, expected);
+
+        // `++`/`--` mudam de classificação conforme o texto em volta da edição
+        let increment_edits = [
+            ("x ++;", 1..2, ""),
+            ("x++;", 1..1, " "),
+            ("a++ 1;", 4..5, ""),
+            ("a-- b;", 0..1, "(a)"),
+        ];
+        for (source, range, replacement) in increment_edits {
+            let mut edited = source.to_string();
+            edited.replace_range(range.clone(), replacement);
+
+            let mut lexer = Lexer::new(source);
+            lexer.tokenize().unwrap();
+            let relexed = lexer.relex(range, replacement).unwrap();
+
+            assert_eq!(relexed, Lexer::new(&edited).tokenize().unwrap(), "{:?} -> {:?}", source, edited);
+        }
+    }
+
+    #[test]
+    fn test_eof_location() {
+        let eof = |source: &str| {
+            let tokens = Lexer::new(source).tokenize().unwrap();
+            let last = tokens.last().unwrap();
+            assert_eq!(last.token, Token::Eof);
+            (last.location.line, last.location.column)
+        };
+
+        // Logo após o último caractere, com ou sem quebra de linha final
+        assert_eq!(eof("var x = 1"), (1, 10));
+        assert_eq!(eof("x\n  y;"), (2, 5));
+        assert_eq!(eof("x\n  y;\n"), (3, 1));
+        assert_eq!(eof("x // fim"), (1, 9));
+        assert_eq!(eof(""), (1, 1));
+    }
+
+    #[test]
+    fn test_length_limits() {
+        // Um identificador de 2 MB é rejeitado sem ser copiado para o token
+        let source = format!("var {}: int = 1;", "a".repeat(2 * 1024 * 1024));
+        let error = Lexer::new(&source).tokenize().unwrap_err();
+        assert!(matches!(
+            &error,
+            CompilerError::LexicalError { line: 1, column: 5, message }
+                if message == "Identificador excede o limite de 1024 bytes"
+        ), "{}", error);
+
+        let tokens = Lexer::new("var contador_total: int = 1;").tokenize().unwrap();
+        assert_eq!(tokens[1].token, Token::Identifier("contador_total".to_string()));
+
+        // Limites configuráveis, inclusive para strings
+        let config = LexerConfig { max_identifier_length: 4, max_string_length: 3, ..LexerConfig::default() };
+        assert!(Lexer::with_config("abcd \"abc\"", config.clone()).tokenize().is_ok());
+        let error = Lexer::with_config("abcde", config.clone()).tokenize().unwrap_err();
+        assert!(error.to_string().contains("Identificador excede o limite de 4 bytes"), "{}", error);
+        let error = Lexer::with_config("x = \"abcd\";", config).tokenize().unwrap_err();
+        assert!(matches!(error, CompilerError::LexicalError { column: 5, .. }), "{}", error);
+        assert!(error.to_string().contains("String excede o limite de 3 bytes"), "{}", error);
+    }
+
+    #[test]
+    fn test_lexeme() {
+        let source = "var contador: float = 2.50f; // fim";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+
+        let identifier = &tokens[1];
+        assert_eq!(identifier.token, Token::Identifier("contador".to_string()));
+        let start = identifier.location.column - 1;
+        assert_eq!(identifier.lexeme, &source[start..start + identifier.location.length]);
+        assert_eq!(identifier.lexeme, "contador");
+
+        // O texto original é preservado mesmo quando o valor o normaliza
+        assert_eq!(tokens[5].token, Token::Float(2.5));
+        assert_eq!(tokens[5].lexeme, "2.50f");
+        assert_eq!(tokens.last().unwrap().lexeme, "");
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let string = |source: &str| match Lexer::new(source).tokenize().unwrap()[0].token {
+            Token::String(ref s) => s.clone(),
+            ref other => panic!("Esperada uma string, encontrado {:?}", other),
+        };
+
+        assert_eq!(string(r#""\x41""#), "A");
+        assert_eq!(string(r#""\u{41}""#), "A");
+        assert_eq!(string(r#""\u{1F600}!""#), "😀!");
+        assert_eq!(string(r#""a\tb\n\"c\"\\""#), "a\tb\n\"c\"\\");
+
+        // A coluna do erro é a da barra do escape
+        let error = Lexer::new(r#"x = "ok \u{110000}";"#).tokenize().unwrap_err();
+        assert!(matches!(
+            &error,
+            CompilerError::LexicalError { line: 1, column: 9, message } if message.contains("\\u{110000}")
+        ), "{}", error);
+
+        for (source, column) in [(r#""\x4""#, 2), (r#""\xFF""#, 2), (r#""ab\u41""#, 4), (r#""\u{}""#, 2), (r#""\q""#, 2)] {
+            let error = Lexer::new(source).tokenize().unwrap_err();
+            assert!(matches!(error, CompilerError::LexicalError { column: c, .. } if c == column), "{}: {}", source, error);
+        }
+    }
+
+    #[test]
+    fn test_increment_tokens_only_after_a_name() {
+        let tokens = |source: &str| -> Vec<Token> {
+            Lexer::new(source).tokenize().unwrap().into_iter().map(|info| info.token).collect()
+        };
+        let name = |name: &str| Token::Identifier(name.to_string());
+
+        assert_eq!(tokens("i++;"), [name("i"), Token::PlusPlus, Token::Semicolon, Token::Eof]);
+        assert_eq!(tokens("i--"), [name("i"), Token::MinusMinus, Token::Eof]);
+
+        // Seguido de um operando, ou sem um nome antes, são dois operadores
+        assert_eq!(tokens("a--1"), [name("a"), Token::Minus, Token::Minus, Token::Integer(1), Token::Eof]);
+        assert_eq!(tokens("a-- b"), [name("a"), Token::Minus, Token::Minus, name("b"), Token::Eof]);
+        assert_eq!(tokens("a - --b"), [name("a"), Token::Minus, Token::Minus, Token::Minus, name("b"), Token::Eof]);
+        assert_eq!(tokens("1 ++2"), [Token::Integer(1), Token::Plus, Token::Plus, Token::Integer(2), Token::Eof]);
+
+        let tokens = Lexer::new("a--1").tokenize().unwrap();
+        let positions: Vec<(usize, &str)> = tokens.iter().map(|info| (info.location.column, info.lexeme.as_str())).collect();
+        assert_eq!(positions, [(1, "a"), (2, "-"), (3, "-"), (4, "1"), (5, "")]);
+    }
+}
@@ -0,0 +1,469 @@
+use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use crate::ast::*;
+use crate::error::{CompilerError, CompilerResult};
+use crate::codegen::LoopLabels;
+use crate::semantic::ExpressionTypes;
+
+/// Valor já calculado: tipo LLVM e operando (`%.t3`, `42`, `true`)
+#[derive(Debug, Clone)]
+struct Value {
+    ty: &'static str,
+    operand: String,
+}
+
+/// Gera LLVM IR textual para o subconjunto da linguagem com `int` e `bool`.
+/// Variáveis locais ficam em `alloca`s no bloco de entrada e são acessadas
+/// com `load`/`store`; o `mem2reg` do LLVM as promove a registradores.
+///
+/// Nomes do programa ficam em um espaço próprio (`%p.x` para parâmetros,
+/// `%v.x.addr` para alocações), e temporários e rótulos do gerador começam
+/// com `.` (`%.t1`, `.then1`), que nenhum identificador produz.
+#[derive(Debug, Default)]
+pub struct LlvmGenerator {
+    expression_types: ExpressionTypes,
+    /// Tipo de retorno das funções definidas no programa
+    functions: HashMap<String, Type>,
+    /// Funções chamadas mas não definidas no programa (built-ins), emitidas
+    /// com `declare`
+    external_functions: IndexMap<String, String>,
+    globals: HashMap<String, &'static str>,
+    // Estado da função em geração
+    allocas: String,
+    body: String,
+    scopes: Vec<HashMap<String, (String, &'static str)>>,
+    slot_names: HashSet<String>,
+    return_type: Option<&'static str>,
+    loops: Vec<LoopLabels>,
+    /// O bloco atual já terminou em `br`/`ret`
+    terminated: bool,
+    temp_counter: usize,
+    label_counter: usize,
+}
+
+impl LlvmGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Usa os tipos resolvidos pela análise semântica, necessários para
+    /// declarar as funções externas
+    pub fn with_expression_types(mut self, expression_types: ExpressionTypes) -> Self {
+        self.expression_types = expression_types;
+        self
+    }
+
+    pub fn generate(&mut self, program: &Program) -> CompilerResult<String> {
+        for statement in &program.statements {
+            if let Statement::Function(func) = statement {
+                self.functions.insert(func.name.clone(), func.return_type.clone());
+            }
+        }
+
+        let mut globals = String::new();
+        let mut functions = String::new();
+        for statement in &program.statements {
+            match statement {
+                Statement::Declaration(decl) => globals.push_str(&self.generate_global(decl)?),
+                Statement::Function(func) => functions.push_str(&self.generate_function(func)?),
+                Statement::Empty(_) => {}
+                other => {
+                    return Err(CompilerError::codegen(format!(
+                        "LLVM IR: só declarações e funções são aceitas no nível superior (linha {})",
+                        other.location().line
+                    )));
+                }
+            }
+        }
+
+        let mut module = String::from("; ModuleID = 'ruscompile'\nsource_filename = \"ruscompile\"\n\n");
+        if !globals.is_empty() {
+            module.push_str(&globals);
+            module.push('\n');
+        }
+        module.push_str(&functions);
+        for declaration in self.external_functions.values() {
+            module.push_str(declaration);
+            module.push('\n');
+        }
+
+        Ok(module)
+    }
+
+    fn llvm_type(ty: &Type) -> CompilerResult<&'static str> {
+        match ty {
+            Type::Int => Ok("i64"),
+            Type::Bool => Ok("i1"),
+            Type::Void => Ok("void"),
+            other => Err(Self::unsupported(&format!("o tipo {}", other))),
+        }
+    }
+
+    fn unsupported(what: &str) -> CompilerError {
+        CompilerError::codegen(format!("LLVM IR suporta apenas int e bool; {} não é suportado", what))
+    }
+
+    /// Tipo da variável declarada; em `var x = ...` é o tipo do
+    /// inicializador registrado pela análise semântica
+    fn declaration_type(&self, decl: &DeclarationStatement) -> CompilerResult<&'static str> {
+        match (&decl.var_type, &decl.initializer) {
+            (Type::Inferred, Some(initializer)) => match self.expression_types.get(initializer) {
+                Some(var_type) => Self::llvm_type(var_type),
+                None => Err(CompilerError::codegen(format!(
+                    "tipo de '{}' desconhecido; execute a análise semântica",
+                    decl.name
+                ))),
+            },
+            (var_type, _) => Self::llvm_type(var_type),
+        }
+    }
+
+    fn generate_global(&mut self, decl: &DeclarationStatement) -> CompilerResult<String> {
+        let ty = self.declaration_type(decl)?;
+        let value = match &decl.initializer {
+            None => "0".to_string(),
+            Some(Expression::Literal(literal)) => Self::literal(&literal.value)?.operand,
+            Some(Expression::Unary(unary)) if unary.operator == UnaryOperator::Minus => match unary.operand.as_ref() {
+                Expression::Literal(LiteralExpression { value: Literal::Integer(n), .. }) => format!("-{}", n),
+                _ => return Err(Self::unsupported("inicializador global não constante")),
+            },
+            Some(_) => return Err(Self::unsupported("inicializador global não constante")),
+        };
+
+        self.globals.insert(decl.name.clone(), ty);
+        Ok(format!("@{} = global {} {}\n", decl.name, ty, value))
+    }
+
+    fn generate_function(&mut self, func: &FunctionStatement) -> CompilerResult<String> {
+        let return_type = Self::llvm_type(&func.return_type)?;
+        self.allocas.clear();
+        self.body.clear();
+        self.scopes = vec![HashMap::new()];
+        self.slot_names.clear();
+        self.return_type = Some(return_type);
+        self.terminated = false;
+        self.temp_counter = 0;
+
+        // Parâmetros chegam como valores e são copiados para alocações locais
+        let mut parameters = Vec::new();
+        for param in &func.parameters {
+            let ty = Self::llvm_type(&param.param_type)?;
+            let slot = self.declare_local(&param.name, ty);
+            parameters.push(format!("{} %p.{}", ty, param.name));
+            self.emit(&format!("store {} %p.{}, ptr {}", ty, param.name, slot));
+        }
+
+        for statement in &func.body.statements {
+            self.generate_statement(statement)?;
+        }
+
+        // Retorno implícito ao cair no fim da função
+        if !self.terminated {
+            match return_type {
+                "void" => self.terminate("ret void"),
+                "i1" => self.terminate("ret i1 false"),
+                ty => self.terminate(&format!("ret {} 0", ty)),
+            }
+        }
+
+        self.return_type = None;
+        Ok(format!(
+            "define {} @{}({}) {{\nentry:\n{}{}}}\n\n",
+            return_type,
+            func.name,
+            parameters.join(", "),
+            self.allocas,
+            self.body
+        ))
+    }
+
+    fn generate_statement(&mut self, statement: &Statement) -> CompilerResult<()> {
+        match statement {
+            Statement::Expression(expr_stmt) => {
+                self.generate_expression(&expr_stmt.expression)?;
+            }
+            Statement::Declaration(decl) => {
+                let ty = self.declaration_type(decl)?;
+                // O inicializador ainda enxerga uma variável externa de mesmo nome
+                let value = match &decl.initializer {
+                    Some(initializer) => self.generate_expression(initializer)?.operand,
+                    None if ty == "i1" => "false".to_string(),
+                    None => "0".to_string(),
+                };
+                let slot = self.declare_local(&decl.name, ty);
+                self.emit(&format!("store {} {}, ptr {}", ty, value, slot));
+            }
+            Statement::Assignment(assign) => {
+                let value = self.generate_expression(&assign.value)?;
+                let (slot, ty) = self.lookup(&assign.target)?;
+                self.emit(&format!("store {} {}, ptr {}", ty, value.operand, slot));
+            }
+            Statement::If(if_stmt) => {
+                let id = self.next_label();
+                let end_label = format!(".endif{}", id);
+                let else_label = match &if_stmt.else_branch {
+                    Some(_) => format!(".else{}", id),
+                    None => end_label.clone(),
+                };
+
+                let condition = self.generate_expression(&if_stmt.condition)?;
+                self.terminate(&format!(
+                    "br i1 {}, label %.then{}, label %{}",
+                    condition.operand, id, else_label
+                ));
+
+                self.start_block(&format!(".then{}", id));
+                self.generate_statement(&if_stmt.then_branch)?;
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    if !self.terminated {
+                        self.terminate(&format!("br label %{}", end_label));
+                    }
+                    self.start_block(&else_label);
+                    self.generate_statement(else_branch)?;
+                }
+                self.start_block(&end_label);
+            }
+            Statement::While(while_stmt) => {
+                let id = self.next_label();
+                let start_label = format!(".while{}", id);
+                let end_label = format!(".endwhile{}", id);
+
+                self.start_block(&start_label);
+                let condition = self.generate_expression(&while_stmt.condition)?;
+                self.terminate(&format!(
+                    "br i1 {}, label %.body{}, label %{}",
+                    condition.operand, id, end_label
+                ));
+
+                self.start_block(&format!(".body{}", id));
+                self.loops.push(LoopLabels {
+                    name: while_stmt.label.clone(),
+                    start: start_label.clone(),
+                    end: end_label.clone(),
+                });
+                let result = self.generate_statement(&while_stmt.body);
+                self.loops.pop();
+                result?;
+
+                if !self.terminated {
+                    self.terminate(&format!("br label %{}", start_label));
+                }
+                self.start_block(&end_label);
+            }
+            Statement::Break(break_stmt) => {
+                let target = LoopLabels::find(&self.loops, break_stmt.label.as_deref())
+                    .map(|target| target.end.clone())
+                    .ok_or_else(|| CompilerError::codegen("'break' fora de um laço"))?;
+                self.terminate(&format!("br label %{}", target));
+            }
+            Statement::Continue(continue_stmt) => {
+                let target = LoopLabels::find(&self.loops, continue_stmt.label.as_deref())
+                    .map(|target| target.start.clone())
+                    .ok_or_else(|| CompilerError::codegen("'continue' fora de um laço"))?;
+                self.terminate(&format!("br label %{}", target));
+            }
+            Statement::Return(return_stmt) => match &return_stmt.value {
+                Some(value) => {
+                    let value = self.generate_expression(value)?;
+                    self.terminate(&format!("ret {} {}", value.ty, value.operand));
+                }
+                None => self.terminate("ret void"),
+            },
+            Statement::Block(block) => {
+                self.scopes.push(HashMap::new());
+                let result = block.statements.iter().try_for_each(|statement| self.generate_statement(statement));
+                self.scopes.pop();
+                result?;
+            }
+            Statement::Empty(_) => {}
+            Statement::Error(_) => return Err(CompilerError::codegen("o programa contém erros de sintaxe")),
+            Statement::Function(func) => {
+                return Err(CompilerError::codegen(format!(
+                    "funções aninhadas não são suportadas: '{}'",
+                    func.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn generate_expression(&mut self, expression: &Expression) -> CompilerResult<Value> {
+        match expression {
+            Expression::Error(_) => Err(CompilerError::codegen("o programa contém erros de sintaxe")),
+            Expression::Literal(literal) => Self::literal(&literal.value),
+            Expression::Identifier(identifier) => {
+                let (slot, ty) = self.lookup(&identifier.name)?;
+                Ok(self.instruction(ty, &format!("load {}, ptr {}", ty, slot)))
+            }
+            Expression::Binary(binary) => {
+                let left = self.generate_expression(&binary.left)?;
+                let right = self.generate_expression(&binary.right)?;
+                let operands = format!("{} {}, {}", left.ty, left.operand, right.operand);
+
+                let (ty, instruction) = match binary.operator {
+                    BinaryOperator::Add => (left.ty, "add"),
+                    BinaryOperator::Subtract => (left.ty, "sub"),
+                    BinaryOperator::Multiply => (left.ty, "mul"),
+                    BinaryOperator::Divide => (left.ty, "sdiv"),
+                    BinaryOperator::Modulo => (left.ty, "srem"),
+                    BinaryOperator::And => ("i1", "and"),
+                    BinaryOperator::Or => ("i1", "or"),
+                    BinaryOperator::Equal => ("i1", "icmp eq"),
+                    BinaryOperator::NotEqual => ("i1", "icmp ne"),
+                    BinaryOperator::LessThan => ("i1", "icmp slt"),
+                    BinaryOperator::LessThanEqual => ("i1", "icmp sle"),
+                    BinaryOperator::GreaterThan => ("i1", "icmp sgt"),
+                    BinaryOperator::GreaterThanEqual => ("i1", "icmp sge"),
+                };
+                Ok(self.instruction(ty, &format!("{} {}", instruction, operands)))
+            }
+            Expression::Unary(unary) => {
+                let operand = self.generate_expression(&unary.operand)?;
+                let instruction = match unary.operator {
+                    UnaryOperator::Minus => format!("sub {} 0, {}", operand.ty, operand.operand),
+                    UnaryOperator::Not => format!("xor {} {}, true", operand.ty, operand.operand),
+                    UnaryOperator::Negate => format!("xor {} {}, -1", operand.ty, operand.operand),
+                };
+                Ok(self.instruction(operand.ty, &instruction))
+            }
+            Expression::Call(call) => self.generate_call(expression, call),
+            Expression::Assignment(assign) => {
+                let value = self.generate_expression(&assign.value)?;
+                let (slot, ty) = self.lookup(&assign.target)?;
+                self.emit(&format!("store {} {}, ptr {}", ty, value.operand, slot));
+                Ok(value)
+            }
+            Expression::Cast(cast) => {
+                let value = self.generate_expression(&cast.expression)?;
+                let target = Self::llvm_type(&cast.target_type)?;
+                if value.ty == target {
+                    Ok(value)
+                } else {
+                    // Única conversão entre int e bool aceita pela análise
+                    Ok(self.instruction(target, &format!("zext {} {} to {}", value.ty, value.operand, target)))
+                }
+            }
+            Expression::IndirectCall(_) => Err(Self::unsupported("chamada indireta")),
+        }
+    }
+
+    fn generate_call(&mut self, expression: &Expression, call: &CallExpression) -> CompilerResult<Value> {
+        if self.find_local(&call.function).is_some() || self.globals.contains_key(&call.function) {
+            return Err(Self::unsupported("chamada por variável"));
+        }
+
+        let mut arguments = Vec::new();
+        for argument in &call.arguments {
+            arguments.push(self.generate_expression(argument)?);
+        }
+
+        let return_type = match self.functions.get(&call.function) {
+            Some(return_type) => Self::llvm_type(return_type)?,
+            None => {
+                // Built-in: a assinatura vem dos tipos da análise semântica
+                let return_type = self.expression_types.get(expression).ok_or_else(|| {
+                    CompilerError::codegen(format!(
+                        "tipo de retorno de '{}' desconhecido; execute a análise semântica",
+                        call.function
+                    ))
+                })?;
+                let return_type = Self::llvm_type(return_type)?;
+                let parameters: Vec<&str> = arguments.iter().map(|argument| argument.ty).collect();
+                self.external_functions.entry(call.function.clone()).or_insert_with(|| {
+                    format!("declare {} @{}({})", return_type, call.function, parameters.join(", "))
+                });
+                return_type
+            }
+        };
+
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| format!("{} {}", argument.ty, argument.operand))
+            .collect();
+        let instruction = format!("call {} @{}({})", return_type, call.function, arguments.join(", "));
+
+        if return_type == "void" {
+            self.emit(&instruction);
+            return Ok(Value { ty: "void", operand: String::new() });
+        }
+        Ok(self.instruction(return_type, &instruction))
+    }
+
+    fn literal(literal: &Literal) -> CompilerResult<Value> {
+        match literal {
+            Literal::Integer(n) => Ok(Value { ty: "i64", operand: n.to_string() }),
+            Literal::Boolean(b) => Ok(Value { ty: "i1", operand: b.to_string() }),
+            other => Err(Self::unsupported(&format!("o literal {}", other))),
+        }
+    }
+
+    /// Emite `%.tN = <instruction>` e retorna o registrador com o resultado
+    fn instruction(&mut self, ty: &'static str, instruction: &str) -> Value {
+        self.temp_counter += 1;
+        let operand = format!("%.t{}", self.temp_counter);
+        self.emit(&format!("{} = {}", operand, instruction));
+        Value { ty, operand }
+    }
+
+    /// Reserva a alocação de uma variável no bloco de entrada; nomes
+    /// sombreados ganham um sufixo para continuarem únicos
+    fn declare_local(&mut self, name: &str, ty: &'static str) -> String {
+        let mut slot = format!("%v.{}.addr", name);
+        let mut suffix = 1;
+        while !self.slot_names.insert(slot.clone()) {
+            suffix += 1;
+            slot = format!("%v.{}.addr{}", name, suffix);
+        }
+
+        self.allocas.push_str(&format!("  {} = alloca {}\n", slot, ty));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), (slot.clone(), ty));
+        }
+        slot
+    }
+
+    fn find_local(&self, name: &str) -> Option<&(String, &'static str)> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup(&self, name: &str) -> CompilerResult<(String, &'static str)> {
+        if let Some((slot, ty)) = self.find_local(name) {
+            return Ok((slot.clone(), ty));
+        }
+        match self.globals.get(name) {
+            Some(ty) => Ok((format!("@{}", name), ty)),
+            None => Err(CompilerError::codegen(format!("Variável '{}' não encontrada", name))),
+        }
+    }
+
+    fn next_label(&mut self) -> usize {
+        self.label_counter += 1;
+        self.label_counter
+    }
+
+    /// Instruções após um `br`/`ret` ficam em um bloco próprio, inalcançável
+    fn emit(&mut self, instruction: &str) {
+        if self.terminated {
+            let label = format!(".dead{}", self.next_label());
+            self.body.push_str(&format!("{}:\n", label));
+            self.terminated = false;
+        }
+        self.body.push_str(&format!("  {}\n", instruction));
+    }
+
+    fn terminate(&mut self, instruction: &str) {
+        self.emit(instruction);
+        self.terminated = true;
+    }
+
+    /// Inicia um bloco básico, ligando o bloco anterior a ele se ainda não
+    /// tiver terminado
+    fn start_block(&mut self, label: &str) {
+        if !self.terminated {
+            self.body.push_str(&format!("  br label %{}\n", label));
+        }
+        self.body.push_str(&format!("{}:\n", label));
+        self.terminated = false;
+    }
+}
@@ -1,20 +1,21 @@
 use std::path::PathBuf;
 use anyhow::Result;
-use clap::Parser;
-
-mod lexer;
-mod parser;
-mod ast;
-mod semantic;
-mod codegen;
-mod error;
-mod utils;
-
-use error::CompilerError;
-use lexer::Lexer;
-use parser::Parser as AstParser;
-use semantic::SemanticAnalyzer;
-use codegen::CodeGenerator;
+use clap::{Parser, ValueEnum};
+
+use ruscompile::Compiler;
+use ruscompile::error::{CompilerError, Severity};
+use ruscompile::lexer::Lexer;
+use ruscompile::parser::DEFAULT_MAX_ERRORS;
+use ruscompile::utils::{cfg_to_dot, CompilerConfig, MAX_OPTIMIZATION_LEVEL};
+
+/// Formato da saída gerada
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Emit {
+    /// Assembly x86-64 (NASM)
+    Asm,
+    /// LLVM IR textual (apenas int e bool)
+    LlvmIr,
+}
 
 #[derive(Parser)]
 #[command(name = "ruscompile")]
@@ -38,59 +39,126 @@ struct Cli {
     ast: bool,
 
     /// Mostrar código assembly gerado
-    #[arg(short, long)]
+    #[arg(short = 's', long)]
     assembly: bool,
 
     /// Nível de otimização (0-3)
-    #[arg(short, long, default_value = "0")]
+    #[arg(
+        short = 'O',
+        long,
+        default_value = "0",
+        value_parser = clap::value_parser!(u8).range(0..=MAX_OPTIMIZATION_LEVEL as i64)
+    )]
     optimization: u8,
+
+    /// Número máximo de erros de sintaxe reportados
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_ERRORS)]
+    max_errors: usize,
+
+    /// Mostrar o tempo gasto em cada fase da compilação
+    #[arg(long)]
+    time_report: bool,
+
+    /// Mostrar o grafo de fluxo de controle de cada função (formato DOT)
+    #[arg(long)]
+    dump_cfg: bool,
+
+    /// Formato da saída gerada
+    #[arg(long, value_enum, default_value_t = Emit::Asm)]
+    emit: Emit,
+
+    /// Apenas verificar o arquivo: mostra "OK" ou os diagnósticos, sem
+    /// gerar saída
+    #[arg(long)]
+    validate: bool,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    // Pânicos internos são reportados como erros, com a posição
+    ruscompile::install_panic_hook();
 
     // Ler arquivo fonte
     let source = std::fs::read_to_string(&cli.input)
         .map_err(|e| CompilerError::FileReadError(cli.input.clone(), e))?;
 
+    if cli.validate {
+        let (_, diagnostics) = Compiler::new().compile_str_with_diagnostics(&source);
+        for diagnostic in &diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+        if errors > 0 {
+            anyhow::bail!("Validação falhou: {} erro(s)", errors);
+        }
+        println!("OK");
+        return Ok(());
+    }
+
     println!("Compilando: {}", cli.input.display());
 
-    // Análise léxica
-    let mut lexer = Lexer::new(&source);
-    let tokens = lexer.tokenize()?;
+    let mut compiler = Compiler::with_config(CompilerConfig {
+        _optimization_level: cli.optimization,
+        max_errors: cli.max_errors,
+        ..CompilerConfig::default()
+    })?;
+
+    // Todos os erros (de sintaxe e semânticos) e avisos de uma vez
+    let (ast, diagnostics) = compiler.compile_str_with_diagnostics(&source);
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    if errors > 0 {
+        anyhow::bail!("Compilação abortada: {} erro(s)", errors);
+    }
 
     if cli.tokens {
         println!("\n=== TOKENS ===");
-        for token in &tokens {
+        for token in Lexer::new(&source).tokenize()? {
             println!("{:?}", token);
         }
     }
 
-    // Análise sintática
-    let mut parser = AstParser::new(tokens);
-    let ast = parser.parse()?;
-
     if cli.ast {
         println!("\n=== AST ===");
         println!("{:#?}", ast);
     }
 
-    // Análise semântica
-    let mut analyzer = SemanticAnalyzer::new();
-    analyzer.analyze(&ast)?;
+    if cli.dump_cfg {
+        println!("\n=== CFG ===");
+        print!("{}", cfg_to_dot(&ast));
+    }
 
-    // Geração de código
-    let mut codegen = CodeGenerator::new(cli.optimization);
-    let assembly = codegen.generate(&ast)?;
+    let assembly = match cli.emit {
+        Emit::Asm => compiler.compile(&source)?,
+        Emit::LlvmIr => compiler.emit_llvm_ir(&source)?,
+    };
+
+    if cli.time_report {
+        match cli.emit {
+            Emit::Asm => {
+                println!();
+                compiler.get_stats().print_time_report();
+            }
+            Emit::LlvmIr => eprintln!("--time-report mede apenas a compilação para assembly (--emit asm)"),
+        }
+    }
 
     if cli.assembly {
-        println!("\n=== ASSEMBLY ===");
+        match cli.emit {
+            Emit::Asm => println!("\n=== ASSEMBLY ==="),
+            Emit::LlvmIr => println!("\n=== LLVM IR ==="),
+        }
         println!("{}", assembly);
     }
 
     // Salvar arquivo de saída
     let output_path = cli.output.unwrap_or_else(|| {
-        cli.input.with_extension("s")
+        match cli.emit {
+            Emit::Asm => cli.input.with_extension("s"),
+            Emit::LlvmIr => cli.input.with_extension("ll"),
+        }
     });
 
     std::fs::write(&output_path, assembly)
@@ -98,4 +166,40 @@ fn main() -> Result<()> {
 
     println!("Compilação concluída: {}", output_path.display());
     Ok(())
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_optimization_range() {
+        for level in ["0", "1", "2", "3"] {
+            let cli = Cli::try_parse_from(["ruscompile", "x.src", "--optimization", level]);
+            assert!(cli.is_ok());
+        }
+
+        let cli = Cli::try_parse_from(["ruscompile", "x.src", "--optimization", "9"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_emit() {
+        let cli = Cli::try_parse_from(["ruscompile", "x.src"]).unwrap();
+        assert_eq!(cli.emit, Emit::Asm);
+
+        let cli = Cli::try_parse_from(["ruscompile", "x.src", "--emit", "llvm-ir"]).unwrap();
+        assert_eq!(cli.emit, Emit::LlvmIr);
+
+        assert!(Cli::try_parse_from(["ruscompile", "x.src", "--emit", "wasm"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_validate() {
+        let cli = Cli::try_parse_from(["ruscompile", "x.src"]).unwrap();
+        assert!(!cli.validate);
+
+        let cli = Cli::try_parse_from(["ruscompile", "x.src", "--validate"]).unwrap();
+        assert!(cli.validate);
+    }
+}
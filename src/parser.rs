@@ -1,18 +1,95 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use crate::ast::*;
 use crate::error::{CompilerError, CompilerResult};
 use crate::lexer::{Token, TokenInfo};
 
+/// Número padrão de erros de sintaxe reportados antes de abortar
+pub const DEFAULT_MAX_ERRORS: usize = 20;
+
+/// Configurações do parser
+#[derive(Debug, Clone)]
+pub struct ParserConfig {
+    /// Máximo de erros coletados por `parse_with_recovery`
+    pub max_errors: usize,
+    /// Exige parênteses nas condições de `if`/`while`. Sem eles, como em
+    /// `if x > 0 { ... }`, o corpo precisa ser um bloco
+    pub require_paren_conditions: bool,
+    /// Aceita uma vírgula antes do `)` em argumentos e parâmetros, como em
+    /// `add(1, 2,)`
+    pub allow_trailing_commas: bool,
+    /// Exige chaves nos corpos de `if`, `else` e `while`, evitando a
+    /// ambiguidade do `else` pendente em `if (a) if (b) x(); else y();`.
+    /// `else if` continua permitido.
+    pub require_braces: bool,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            max_errors: DEFAULT_MAX_ERRORS,
+            require_paren_conditions: true,
+            allow_trailing_commas: false,
+            require_braces: false,
+        }
+    }
+}
+
 pub struct Parser {
     tokens: Vec<TokenInfo>,
+    docs: BTreeMap<usize, String>,
     current: usize,
+    config: ParserConfig,
+    errors: Vec<CompilerError>,
+    recovering: bool,
+    /// Token onde parou a recuperação da última expressão com erro; um erro
+    /// do comando nesse mesmo token é consequência daquele
+    expression_recovered_at: Option<usize>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<TokenInfo>) -> Self {
+        Self::with_config(tokens, ParserConfig::default())
+    }
+
+    pub fn with_config(tokens: Vec<TokenInfo>, config: ParserConfig) -> Self {
+        let (tokens, docs) = Self::extract_doc_comments(tokens);
+
         Self {
             tokens,
+            docs,
             current: 0,
+            config,
+            errors: Vec::new(),
+            recovering: false,
+            expression_recovered_at: None,
+        }
+    }
+
+    /// Remove os comentários `///` da sequência de tokens, associando cada
+    /// grupo consecutivo ao índice do token que o segue
+    fn extract_doc_comments(tokens: Vec<TokenInfo>) -> (Vec<TokenInfo>, BTreeMap<usize, String>) {
+        let mut filtered = Vec::with_capacity(tokens.len());
+        let mut docs = BTreeMap::new();
+        let mut pending: Vec<String> = Vec::new();
+
+        for token_info in tokens {
+            if let Token::DocComment(text) = token_info.token {
+                pending.push(text);
+                continue;
+            }
+
+            if !pending.is_empty() {
+                docs.insert(filtered.len(), pending.join("\n"));
+                pending.clear();
+            }
+            filtered.push(token_info);
         }
+
+        (filtered, docs)
     }
 
     pub fn parse(&mut self) -> CompilerResult<Program> {
@@ -27,9 +104,119 @@ impl Parser {
         Ok(Program { statements })
     }
 
+    /// Analisa exatamente uma expressão, como em um REPL. Tokens restantes
+    /// após a expressão (exceto `Eof`) são um erro.
+    pub fn parse_expression(&mut self) -> CompilerResult<Expression> {
+        let expression = self.expression()?;
+
+        if !self.is_at_end() {
+            let token_info = self.peek();
+            return Err(CompilerError::syntax(
+                token_info.location.line,
+                token_info.location.column,
+                format!("Token inesperado após a expressão: '{}'", token_info.lexeme),
+            ));
+        }
+
+        Ok(expression)
+    }
+
+    /// Analisa o programa inteiro, recuperando-se de erros de sintaxe em vez
+    /// de parar no primeiro. Retorna as declarações válidas e os erros
+    /// encontrados; ao atingir `max_errors`, a análise é interrompida e um
+    /// erro de resumo é adicionado ao final da lista.
+    pub fn parse_with_recovery(&mut self) -> (Program, Vec<CompilerError>) {
+        let mut statements = Vec::new();
+        self.recovering = true;
+
+        while !self.is_at_end() {
+            match self.recovering_declaration() {
+                Ok(Some(stmt)) => statements.push(stmt),
+                Ok(None) => {}
+                Err(summary) => {
+                    self.errors.push(summary);
+                    break;
+                }
+            }
+        }
+
+        self.recovering = false;
+        (Program { statements }, core::mem::take(&mut self.errors))
+    }
+
+    /// Declaração; em `parse_with_recovery`, uma declaração com erro vira
+    /// um `Statement::Error` no lugar
+    fn recovering_declaration(&mut self) -> CompilerResult<Option<Statement>> {
+        let start = self.current;
+        let location = self.peek().location.clone();
+
+        match self.declaration() {
+            Ok(stmt) => Ok(stmt),
+            Err(error) if self.recovering => {
+                // Como em `var x: int = }`: o `;` que falta após a expressão
+                // já reportada não é um segundo erro
+                if self.expression_recovered_at.take() != Some(self.current) {
+                    self.record_error(error)?;
+                }
+                // Garantir progresso antes de sincronizar
+                if self.current == start {
+                    self.advance();
+                }
+                self.synchronize();
+                Ok(Some(Statement::Error(ErrorNode { location })))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn record_error(&mut self, error: CompilerError) -> CompilerResult<()> {
+        self.errors.push(error);
+
+        if self.errors.len() >= self.config.max_errors {
+            // Desligar a recuperação para que o resumo suba até o topo
+            self.recovering = false;
+            return Err(CompilerError::syntax(
+                self.peek().location.line,
+                self.peek().location.column,
+                format!(
+                    "demasiados erros; análise interrompida após {} erros",
+                    self.config.max_errors
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Descarta tokens até o fim da declaração atual ou o início da próxima
+    fn synchronize(&mut self) {
+        while !self.is_at_end() {
+            if self.previous().token == Token::Semicolon {
+                return;
+            }
+
+            match self.peek().token {
+                Token::Var
+                | Token::Const
+                | Token::Func
+                | Token::If
+                | Token::While
+                | Token::Return
+                | Token::Break
+                | Token::Continue
+                | Token::RightBrace => return,
+                _ => {}
+            }
+
+            self.advance();
+        }
+    }
+
     fn declaration(&mut self) -> CompilerResult<Option<Statement>> {
         if self.match_token(Token::Var) {
-            self.var_declaration().map(Some)
+            self.var_declaration(false).map(Some)
+        } else if self.match_token(Token::Const) {
+            self.var_declaration(true).map(Some)
         } else if self.match_token(Token::Func) {
             self.function_declaration().map(Some)
         } else {
@@ -37,7 +224,7 @@ impl Parser {
         }
     }
 
-    fn var_declaration(&mut self) -> CompilerResult<Statement> {
+    fn var_declaration(&mut self, is_const: bool) -> CompilerResult<Statement> {
         let location = self.previous().location.clone();
 
         let name = if let Some(token_info) = self.advance() {
@@ -51,13 +238,13 @@ impl Parser {
                 ));
             }
         } else {
-            return Err(CompilerError::syntax(0, 0, "Esperado nome de variável".to_string()));
+            return Err(self.end_of_file_error("Esperado nome de variável"));
         };
 
         let var_type = if self.match_token(Token::Colon) {
             self.parse_type()?
         } else {
-            Type::Int // Tipo padrão
+            Type::Inferred
         };
 
         let initializer = if self.match_token(Token::Assign) {
@@ -66,18 +253,28 @@ impl Parser {
             None
         };
 
+        if is_const && initializer.is_none() {
+            return Err(CompilerError::syntax(
+                location.line,
+                location.column,
+                format!("Constante '{}' precisa de um inicializador", name),
+            ));
+        }
+
         self.expect(Token::Semicolon)?;
 
         Ok(Statement::Declaration(DeclarationStatement {
             name,
             var_type,
             initializer,
+            is_const,
             location,
         }))
     }
 
     fn function_declaration(&mut self) -> CompilerResult<Statement> {
         let location = self.previous().location.clone();
+        let doc = self.docs.remove(&(self.current - 1));
 
         let name = if let Some(token_info) = self.advance() {
             if let Token::Identifier(name) = &token_info.token {
@@ -90,7 +287,7 @@ impl Parser {
                 ));
             }
         } else {
-            return Err(CompilerError::syntax(0, 0, "Esperado nome de função".to_string()));
+            return Err(self.end_of_file_error("Esperado nome de função"));
         };
 
         self.expect(Token::LeftParen)?;
@@ -98,9 +295,9 @@ impl Parser {
         let mut parameters = Vec::new();
         if !self.check(Token::RightParen) {
             loop {
-                let param_name = if let Some(token_info) = self.advance() {
+                let (param_name, param_location) = if let Some(token_info) = self.advance() {
                     if let Token::Identifier(name) = &token_info.token {
-                        name.clone()
+                        (name.clone(), token_info.location.clone())
                     } else {
                         return Err(CompilerError::syntax(
                             token_info.location.line,
@@ -109,7 +306,7 @@ impl Parser {
                         ));
                     }
                 } else {
-                    return Err(CompilerError::syntax(0, 0, "Esperado nome de parâmetro".to_string()));
+                    return Err(self.end_of_file_error("Esperado nome de parâmetro"));
                 };
 
                 self.expect(Token::Colon)?;
@@ -118,10 +315,10 @@ impl Parser {
                 parameters.push(Parameter {
                     name: param_name,
                     param_type,
-                    location: self.previous().location.clone(),
+                    location: param_location,
                 });
 
-                if !self.match_token(Token::Comma) {
+                if !self.list_continues() {
                     break;
                 }
             }
@@ -143,6 +340,7 @@ impl Parser {
             parameters,
             return_type,
             body,
+            doc,
             location,
         }))
     }
@@ -151,9 +349,25 @@ impl Parser {
         if self.match_token(Token::If) {
             self.if_statement()
         } else if self.match_token(Token::While) {
-            self.while_statement()
+            self.while_statement(None)
+        } else if let Some(label) = self.loop_label() {
+            self.expect(Token::While)?;
+            self.while_statement(Some(label))
         } else if self.match_token(Token::Return) {
             self.return_statement()
+        } else if self.match_token(Token::Break) {
+            let location = self.previous().location.clone();
+            let label = self.jump_label();
+            self.expect(Token::Semicolon)?;
+            Ok(Statement::Break(BreakStatement { label, location }))
+        } else if self.match_token(Token::Continue) {
+            let location = self.previous().location.clone();
+            let label = self.jump_label();
+            self.expect(Token::Semicolon)?;
+            Ok(Statement::Continue(ContinueStatement { label, location }))
+        } else if self.match_token(Token::Semicolon) {
+            let location = self.previous().location.clone();
+            Ok(Statement::Empty(EmptyStatement { location }))
         } else if self.match_token(Token::LeftBrace) {
             self.block_statement().map(Statement::Block)
         } else {
@@ -164,13 +378,16 @@ impl Parser {
     fn if_statement(&mut self) -> CompilerResult<Statement> {
         let location = self.previous().location.clone();
 
-        self.expect(Token::LeftParen)?;
-        let condition = self.expression()?;
-        self.expect(Token::RightParen)?;
-
-        let then_branch = Box::new(self.statement()?);
+        let condition = self.condition()?;
+        let then_branch = Box::new(self.conditional_body("if")?);
         let else_branch = if self.match_token(Token::Else) {
+            if self.config.require_braces && !self.check(Token::If) {
+                self.expect_body_block("else")?;
+            }
             Some(Box::new(self.statement()?))
+        } else if self.match_token(Token::Elif) {
+            // `elif (c) ...` é o mesmo que `else if (c) ...`
+            Some(Box::new(self.if_statement()?))
         } else {
             None
         };
@@ -183,22 +400,87 @@ impl Parser {
         }))
     }
 
-    fn while_statement(&mut self) -> CompilerResult<Statement> {
-        let location = self.previous().location.clone();
+    /// Condição de `if`/`while`, entre parênteses se a configuração exigir
+    fn condition(&mut self) -> CompilerResult<Expression> {
+        if !self.config.require_paren_conditions {
+            return self.expression();
+        }
 
         self.expect(Token::LeftParen)?;
         let condition = self.expression()?;
         self.expect(Token::RightParen)?;
+        Ok(condition)
+    }
+
+    /// Corpo de `if`/`while`: um bloco se `require_braces` estiver ativo ou
+    /// se não houver parênteses na condição, pois só o bloco separa a
+    /// condição do corpo
+    fn conditional_body(&mut self, keyword: &str) -> CompilerResult<Statement> {
+        if self.config.require_braces {
+            self.expect_body_block(keyword)?;
+        } else if self.config.require_paren_conditions {
+            return self.statement();
+        }
 
-        let body = Box::new(self.statement()?);
+        self.expect(Token::LeftBrace)?;
+        self.block_statement().map(Statement::Block)
+    }
+
+    fn while_statement(&mut self, label: Option<String>) -> CompilerResult<Statement> {
+        let location = self.previous().location.clone();
+
+        let condition = self.condition()?;
+        let body = Box::new(self.conditional_body("while")?);
 
         Ok(Statement::While(WhileStatement {
             condition,
             body,
+            label,
             location,
         }))
     }
 
+    /// Consome `rotulo:` quando ele precede um laço
+    fn loop_label(&mut self) -> Option<String> {
+        let name = match &self.peek().token {
+            Token::Identifier(name) => name.clone(),
+            _ => return None,
+        };
+        let is_label = matches!(self.tokens.get(self.current + 1), Some(info) if info.token == Token::Colon);
+        if !is_label {
+            return None;
+        }
+
+        self.current += 2;
+        Some(name)
+    }
+
+    /// Rótulo opcional depois de `break`/`continue`
+    fn jump_label(&mut self) -> Option<String> {
+        match &self.peek().token {
+            Token::Identifier(name) => {
+                let name = name.clone();
+                self.advance();
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Com `require_braces`, o corpo de `keyword` precisa começar com `{`
+    fn expect_body_block(&self, keyword: &str) -> CompilerResult<()> {
+        if self.check(Token::LeftBrace) {
+            return Ok(());
+        }
+
+        let location = &self.peek().location;
+        Err(CompilerError::syntax(
+            location.line,
+            location.column,
+            format!("O corpo de '{}' precisa estar entre chaves", keyword),
+        ))
+    }
+
     fn return_statement(&mut self) -> CompilerResult<Statement> {
         let location = self.previous().location.clone();
 
@@ -218,17 +500,13 @@ impl Parser {
         let mut statements = Vec::new();
 
         while !self.check(Token::RightBrace) && !self.is_at_end() {
-            if let Some(stmt) = self.declaration()? {
+            if let Some(stmt) = self.recovering_declaration()? {
                 statements.push(stmt);
             }
         }
 
         if self.is_at_end() {
-            return Err(CompilerError::syntax(
-                self.previous().location.line,
-                self.previous().location.column,
-                "Esperado '}' antes do fim do arquivo".to_string(),
-            ));
+            return Err(self.end_of_file_error("Esperado '}' antes do fim do arquivo"));
         }
 
         self.expect(Token::RightBrace)?;
@@ -241,6 +519,14 @@ impl Parser {
 
     fn expression_statement(&mut self) -> CompilerResult<Statement> {
         let expression = self.expression()?;
+
+        if self.match_token(Token::PlusPlus) {
+            return self.increment_statement(expression, BinaryOperator::Add);
+        }
+        if self.match_token(Token::MinusMinus) {
+            return self.increment_statement(expression, BinaryOperator::Subtract);
+        }
+
         let location = self.previous().location.clone();
 
         self.expect(Token::Semicolon)?;
@@ -251,203 +537,232 @@ impl Parser {
         }))
     }
 
-    fn expression(&mut self) -> CompilerResult<Expression> {
-        self.assignment()
-    }
-
-    fn assignment(&mut self) -> CompilerResult<Expression> {
-        let expr = self.or()?;
-
-        if self.match_token(Token::Assign) {
-            let value = self.assignment()?;
-
-            if let Expression::Identifier(identifier) = expr {
-                return Ok(Expression::Assignment(AssignmentExpression {
-                    target: identifier.name,
-                    value: Box::new(value),
-                    location: self.previous().location.clone(),
-                }));
-            }
+    /// `i++;` e `i--;` equivalem a `i = i + 1;` e `i = i - 1;`
+    fn increment_statement(
+        &mut self,
+        target: Expression,
+        operator: BinaryOperator,
+    ) -> CompilerResult<Statement> {
+        let location = self.previous().location.clone();
 
+        let Expression::Identifier(identifier) = target else {
+            let symbol = if operator == BinaryOperator::Add { "++" } else { "--" };
             return Err(CompilerError::syntax(
-                self.previous().location.line,
-                self.previous().location.column,
-                "Expressão inválida para atribuição".to_string(),
+                location.line,
+                location.column,
+                format!("Operando de '{}' deve ser uma variável", symbol),
             ));
-        }
-
-        Ok(expr)
-    }
+        };
 
-    fn or(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.and()?;
+        self.expect(Token::Semicolon)?;
 
-        while self.match_token(Token::Or) {
-            let operator = BinaryOperator::Or;
-            let right = Box::new(self.and()?);
-            let location = self.previous().location.clone();
+        let one = Expression::Literal(LiteralExpression {
+            value: Literal::Integer(1),
+            location: location.clone(),
+        });
 
-            expr = Expression::Binary(BinaryExpression {
-                left: Box::new(expr),
+        Ok(Statement::Assignment(AssignmentStatement {
+            target: identifier.name.clone(),
+            value: Expression::Binary(BinaryExpression {
+                left: Box::new(Expression::Identifier(identifier)),
                 operator,
-                right,
-                location,
-            });
-        }
-
-        Ok(expr)
+                right: Box::new(one),
+                location: location.clone(),
+            }),
+            location,
+        }))
     }
 
-    fn and(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.equality()?;
-
-        while self.match_token(Token::And) {
-            let operator = BinaryOperator::And;
-            let right = Box::new(self.equality()?);
-            let location = self.previous().location.clone();
-
-            expr = Expression::Binary(BinaryExpression {
-                left: Box::new(expr),
-                operator,
-                right,
-                location,
-            });
+    /// Expressão; em `parse_with_recovery`, uma expressão com erro vira um
+    /// `Expression::Error` no lugar, e o comando que a contém continua
+    fn expression(&mut self) -> CompilerResult<Expression> {
+        let start = self.current;
+
+        match self.operator_expression() {
+            Err(error) if self.recovering => {
+                self.record_error(error)?;
+                self.current = start;
+                let location = self.peek().location.clone();
+                self.skip_expression();
+                self.expression_recovered_at = Some(self.current);
+                Ok(Expression::Error(ErrorNode { location }))
+            }
+            result => result,
         }
-
-        Ok(expr)
     }
 
-    fn equality(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.comparison()?;
-
-        while self.match_token(Token::Equal) || self.match_token(Token::NotEqual) {
-            let operator = if self.previous().token == Token::Equal {
-                BinaryOperator::Equal
-            } else {
-                BinaryOperator::NotEqual
-            };
-            let right = Box::new(self.comparison()?);
-            let location = self.previous().location.clone();
+    /// Descarta os tokens de uma expressão com erro, parando no `,` ou `)`
+    /// que a encerra fora de parênteses, ou no fim ou início de um comando
+    fn skip_expression(&mut self) {
+        let mut depth = 0usize;
 
-            expr = Expression::Binary(BinaryExpression {
-                left: Box::new(expr),
-                operator,
-                right,
-                location,
-            });
+        while !self.is_at_end() {
+            match self.peek().token {
+                Token::LeftParen => depth += 1,
+                Token::RightParen | Token::Comma if depth == 0 => return,
+                Token::RightParen => depth -= 1,
+                Token::Semicolon
+                | Token::LeftBrace
+                | Token::RightBrace
+                | Token::Var
+                | Token::Const
+                | Token::Func
+                | Token::If
+                | Token::While
+                | Token::Return
+                | Token::Break
+                | Token::Continue => return,
+                _ => {}
+            }
+            self.advance();
         }
-
-        Ok(expr)
     }
 
-    fn comparison(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.term()?;
-
-        while self.match_token(Token::LessThan)
-            || self.match_token(Token::LessThanEqual)
-            || self.match_token(Token::GreaterThan)
-            || self.match_token(Token::GreaterThanEqual)
-        {
-            let operator = match self.previous().token {
-                Token::LessThan => BinaryOperator::LessThan,
-                Token::LessThanEqual => BinaryOperator::LessThanEqual,
-                Token::GreaterThan => BinaryOperator::GreaterThan,
-                Token::GreaterThanEqual => BinaryOperator::GreaterThanEqual,
-                _ => unreachable!(),
-            };
-            let right = Box::new(self.term()?);
-            let location = self.previous().location.clone();
-
-            expr = Expression::Binary(BinaryExpression {
-                left: Box::new(expr),
-                operator,
-                right,
-                location,
-            });
-        }
+    /// Expressões com pilhas explícitas de operandos e operadores (no estilo
+    /// Pratt/shunting-yard) em vez de uma função recursiva por nível de
+    /// precedência. Parênteses e operadores prefixos também vão para a
+    /// pilha, então o aninhamento não consome a pilha de chamadas; só os
+    /// argumentos de chamadas recursam.
+    fn operator_expression(&mut self) -> CompilerResult<Expression> {
+        // Cada operando guarda a posição do último token lido ao terminá-lo,
+        // que é a posição atribuída ao operador que o tem à direita
+        let mut operands: Vec<(Expression, Location)> = Vec::new();
+        let mut operators: Vec<PendingOperator> = Vec::new();
+        let mut open_groups = 0usize;
 
-        Ok(expr)
-    }
+        loop {
+            // Posição de operando: prefixos e parênteses até o primário
+            loop {
+                if self.match_token(Token::Not) {
+                    operators.push(PendingOperator::Unary(UnaryOperator::Not));
+                } else if self.match_token(Token::Minus) {
+                    operators.push(PendingOperator::Unary(UnaryOperator::Minus));
+                } else if self.match_token(Token::LeftParen) {
+                    operators.push(PendingOperator::Group);
+                    open_groups += 1;
+                } else {
+                    break;
+                }
+            }
+            let primary = self.primary()?;
+            let operand = self.postfix(primary)?;
+            operands.push((operand, self.previous().location.clone()));
+
+            // Posição de operador: fecha grupos até achar um operador
+            while open_groups > 0 && self.match_token(Token::RightParen) {
+                while !matches!(operators.last(), Some(PendingOperator::Group)) {
+                    Self::reduce(&mut operands, &mut operators)?;
+                }
+                operators.pop();
+                open_groups -= 1;
 
-    fn term(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.factor()?;
+                let (group, _) = operands.pop().expect("operando do grupo na pilha");
+                let operand = self.postfix(group)?;
+                operands.push((operand, self.previous().location.clone()));
+            }
 
-        while self.match_token(Token::Plus) || self.match_token(Token::Minus) {
-            let operator = if self.previous().token == Token::Plus {
-                BinaryOperator::Add
+            if let Some((operator, precedence)) = binary_operator(&self.peek().token) {
+                self.advance();
+                while operators.last().is_some_and(|top| top.binds_before(precedence)) {
+                    Self::reduce(&mut operands, &mut operators)?;
+                }
+                operators.push(PendingOperator::Binary(operator, precedence));
+            } else if self.match_token(Token::Assign) {
+                // Associa à direita: atribuições pendentes ficam na pilha
+                while matches!(
+                    operators.last(),
+                    Some(PendingOperator::Unary(_) | PendingOperator::Binary(..))
+                ) {
+                    Self::reduce(&mut operands, &mut operators)?;
+                }
+                operators.push(PendingOperator::Assign);
             } else {
-                BinaryOperator::Subtract
-            };
-            let right = Box::new(self.factor()?);
-            let location = self.previous().location.clone();
+                break;
+            }
+        }
 
-            expr = Expression::Binary(BinaryExpression {
-                left: Box::new(expr),
-                operator,
-                right,
-                location,
-            });
+        if open_groups > 0 {
+            self.expect(Token::RightParen)?;
+        }
+        while !operators.is_empty() {
+            Self::reduce(&mut operands, &mut operators)?;
         }
 
-        Ok(expr)
+        Ok(operands.pop().expect("sempre resta um operando").0)
     }
 
-    fn factor(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.unary()?;
+    /// Aplica o operador do topo da pilha aos operandos do topo
+    fn reduce(
+        operands: &mut Vec<(Expression, Location)>,
+        operators: &mut Vec<PendingOperator>,
+    ) -> CompilerResult<()> {
+        let operator = operators.pop().expect("operador na pilha");
+        let (right, location) = operands.pop().expect("operando direito na pilha");
 
-        while self.match_token(Token::Star) || self.match_token(Token::Slash) || self.match_token(Token::Percent) {
-            let operator = match self.previous().token {
-                Token::Star => BinaryOperator::Multiply,
-                Token::Slash => BinaryOperator::Divide,
-                Token::Percent => BinaryOperator::Modulo,
-                _ => unreachable!(),
-            };
-            let right = Box::new(self.unary()?);
-            let location = self.previous().location.clone();
-
-            expr = Expression::Binary(BinaryExpression {
-                left: Box::new(expr),
+        let expr = match operator {
+            PendingOperator::Unary(operator) => Expression::Unary(UnaryExpression {
                 operator,
-                right,
-                location,
-            });
-        }
+                operand: Box::new(right),
+                location: location.clone(),
+            }),
+            PendingOperator::Binary(operator, _) => {
+                let (left, _) = operands.pop().expect("operando esquerdo na pilha");
+                Expression::Binary(BinaryExpression {
+                    left: Box::new(left),
+                    operator,
+                    right: Box::new(right),
+                    location: location.clone(),
+                })
+            }
+            PendingOperator::Assign => match operands.pop().expect("alvo da atribuição na pilha") {
+                (Expression::Identifier(identifier), _) => Expression::Assignment(AssignmentExpression {
+                    target: identifier.name,
+                    value: Box::new(right),
+                    location: location.clone(),
+                }),
+                _ => {
+                    return Err(CompilerError::syntax(
+                        location.line,
+                        location.column,
+                        "Expressão inválida para atribuição".to_string(),
+                    ))
+                }
+            },
+            PendingOperator::Group => unreachable!("grupos são fechados por ')'"),
+        };
 
-        Ok(expr)
+        operands.push((expr, location));
+        Ok(())
     }
 
-    fn unary(&mut self) -> CompilerResult<Expression> {
-        if self.match_token(Token::Not) || self.match_token(Token::Minus) {
-            let operator = if self.previous().token == Token::Not {
-                UnaryOperator::Not
-            } else {
-                UnaryOperator::Minus
-            };
-            let operand = Box::new(self.unary()?);
+    /// Chamadas e conversões (`as`) logo após um operando
+    fn postfix(&mut self, mut expr: Expression) -> CompilerResult<Expression> {
+        while self.match_token(Token::LeftParen) {
+            expr = self.finish_call(expr)?;
+        }
+
+        while self.match_token(Token::As) {
             let location = self.previous().location.clone();
+            let target_type = self.parse_type()?;
 
-            return Ok(Expression::Unary(UnaryExpression {
-                operator,
-                operand,
+            expr = Expression::Cast(CastExpression {
+                expression: Box::new(expr),
+                target_type,
                 location,
-            }));
+            });
         }
 
-        self.call()
+        Ok(expr)
     }
 
-    fn call(&mut self) -> CompilerResult<Expression> {
-        let mut expr = self.primary()?;
-
-        loop {
-            if self.match_token(Token::LeftParen) {
-                expr = self.finish_call(expr)?;
-            } else {
-                break;
-            }
+    /// Consome a vírgula entre itens de uma lista entre parênteses; retorna
+    /// se vem outro item. Com `allow_trailing_commas`, uma vírgula seguida
+    /// de `)` encerra a lista.
+    fn list_continues(&mut self) -> bool {
+        if !self.match_token(Token::Comma) {
+            return false;
         }
-
-        Ok(expr)
+        !(self.config.allow_trailing_commas && self.check(Token::RightParen))
     }
 
     fn finish_call(&mut self, callee: Expression) -> CompilerResult<Expression> {
@@ -456,7 +771,7 @@ impl Parser {
         if !self.check(Token::RightParen) {
             loop {
                 arguments.push(self.expression()?);
-                if !self.match_token(Token::Comma) {
+                if !self.list_continues() {
                     break;
                 }
             }
@@ -464,21 +779,20 @@ impl Parser {
 
         let location = self.expect(Token::RightParen)?.location.clone();
 
-        let function_name = if let Expression::Identifier(identifier) = callee {
-            identifier.name
-        } else {
-            return Err(CompilerError::syntax(
-                location.line,
-                location.column,
-                "Esperado nome de função".to_string(),
-            ));
-        };
-
-        Ok(Expression::Call(CallExpression {
-            function: function_name,
-            arguments,
-            location,
-        }))
+        // Chamadas por nome resolvem a função (ou variável) diretamente;
+        // qualquer outra expressão é chamada pelo valor que produz
+        match callee {
+            Expression::Identifier(identifier) => Ok(Expression::Call(CallExpression {
+                function: identifier.name,
+                arguments,
+                location,
+            })),
+            callee => Ok(Expression::IndirectCall(IndirectCallExpression {
+                callee: Box::new(callee),
+                arguments,
+                location,
+            })),
+        }
     }
 
     fn primary(&mut self) -> CompilerResult<Expression> {
@@ -502,23 +816,22 @@ impl Parser {
                     value: Literal::Boolean(*b),
                     location,
                 })),
+                Token::Char(c) => Ok(Expression::Literal(LiteralExpression {
+                    value: Literal::Char(*c),
+                    location,
+                })),
                 Token::Identifier(name) => Ok(Expression::Identifier(IdentifierExpression {
                     name: name.clone(),
                     location,
                 })),
-                Token::LeftParen => {
-                    let expr = self.expression()?;
-                    self.expect(Token::RightParen)?;
-                    Ok(expr)
-                }
                 _ => Err(CompilerError::syntax(
                     location.line,
                     location.column,
-                    format!("Expressão inesperada: {:?}", token_info.token),
+                    format!("Expressão inesperada: '{}'", token_info.lexeme),
                 )),
             }
         } else {
-            Err(CompilerError::syntax(0, 0, "Expressão inesperada no fim do arquivo".to_string()))
+            Err(self.end_of_file_error("Expressão inesperada no fim do arquivo"))
         }
     }
 
@@ -528,8 +841,10 @@ impl Parser {
                 Token::Int => Ok(Type::Int),
                 Token::FloatType => Ok(Type::Float),
                 Token::Bool => Ok(Type::Bool),
+                Token::CharType => Ok(Type::Char),
                 Token::StringType => Ok(Type::String),
                 Token::Void => Ok(Type::Void),
+                Token::LeftParen => self.function_type(),
                 _ => Err(CompilerError::syntax(
                     token_info.location.line,
                     token_info.location.column,
@@ -537,10 +852,33 @@ impl Parser {
                 )),
             }
         } else {
-            Err(CompilerError::syntax(0, 0, "Tipo esperado".to_string()))
+            Err(self.end_of_file_error("Tipo esperado"))
         }
     }
 
+    /// Tipo de função `(T1, T2) -> R`, após o `(` inicial
+    fn function_type(&mut self) -> CompilerResult<Type> {
+        let mut parameters = Vec::new();
+
+        if !self.check(Token::RightParen) {
+            loop {
+                parameters.push(self.parse_type()?);
+                if !self.list_continues() {
+                    break;
+                }
+            }
+        }
+
+        self.expect(Token::RightParen)?;
+        self.expect(Token::Arrow)?;
+        let return_type = self.parse_type()?;
+
+        Ok(Type::Function {
+            parameters,
+            return_type: Box::new(return_type),
+        })
+    }
+
     // Métodos auxiliares
     fn match_token(&mut self, token: Token) -> bool {
         if self.check(token.clone()) {
@@ -555,7 +893,7 @@ impl Parser {
         if self.is_at_end() {
             false
         } else {
-            std::mem::discriminant(&self.peek().token) == std::mem::discriminant(&token)
+            core::mem::discriminant(&self.peek().token) == core::mem::discriminant(&token)
         }
     }
 
@@ -568,6 +906,12 @@ impl Parser {
         }
     }
 
+    /// Erro de sintaxe na posição do `Eof`, logo após o último caractere
+    fn end_of_file_error(&self, message: &str) -> CompilerError {
+        let location = &self.peek().location;
+        CompilerError::syntax(location.line, location.column, message)
+    }
+
     fn is_at_end(&self) -> bool {
         self.peek().token == Token::Eof
     }
@@ -587,8 +931,57 @@ impl Parser {
             Err(CompilerError::syntax(
                 self.peek().location.line,
                 self.peek().location.column,
-                format!("Esperado '{:?}'", token),
+                format!("Esperado '{}'", token),
             ))
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Operador ainda não aplicado na pilha de `Parser::expression`
+enum PendingOperator {
+    Unary(UnaryOperator),
+    Binary(BinaryOperator, u8),
+    Assign,
+    /// `(` ainda não fechado
+    Group,
+}
+
+impl PendingOperator {
+    /// Se este operador, já na pilha, deve ser aplicado antes de empilhar um
+    /// binário de precedência `next`: prefixos ligam mais forte que qualquer
+    /// binário, e binários de mesma precedência associam à esquerda
+    fn binds_before(&self, next: u8) -> bool {
+        match self {
+            PendingOperator::Unary(_) => true,
+            PendingOperator::Binary(_, precedence) => *precedence >= next,
+            PendingOperator::Assign | PendingOperator::Group => false,
+        }
+    }
+}
+
+/// Tabela de operadores binários: token, operador e precedência (maior
+/// liga mais forte). Todos associam à esquerda; um novo operador binário só
+/// precisa de uma linha aqui.
+const BINARY_OPERATORS: [(Token, BinaryOperator, u8); 13] = [
+    (Token::Or, BinaryOperator::Or, 1),
+    (Token::And, BinaryOperator::And, 2),
+    (Token::Equal, BinaryOperator::Equal, 3),
+    (Token::NotEqual, BinaryOperator::NotEqual, 3),
+    (Token::LessThan, BinaryOperator::LessThan, 4),
+    (Token::LessThanEqual, BinaryOperator::LessThanEqual, 4),
+    (Token::GreaterThan, BinaryOperator::GreaterThan, 4),
+    (Token::GreaterThanEqual, BinaryOperator::GreaterThanEqual, 4),
+    (Token::Plus, BinaryOperator::Add, 5),
+    (Token::Minus, BinaryOperator::Subtract, 5),
+    (Token::Star, BinaryOperator::Multiply, 6),
+    (Token::Slash, BinaryOperator::Divide, 6),
+    (Token::Percent, BinaryOperator::Modulo, 6),
+];
+
+/// Operador binário e sua precedência para um token, se houver
+fn binary_operator(token: &Token) -> Option<(BinaryOperator, u8)> {
+    BINARY_OPERATORS
+        .iter()
+        .find(|(candidate, _, _)| candidate == token)
+        .map(|(_, operator, precedence)| (operator.clone(), *precedence))
+}
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::fmt;
 use crate::ast::*;
-use crate::error::{CompilerError, CompilerResult};
+use crate::error::{CompilerError, CompilerResult, Diagnostic, Severity};
+use crate::utils::describe_expression;
 
 #[derive(Debug, Clone)]
 pub struct Symbol {
@@ -9,6 +11,10 @@ pub struct Symbol {
     pub is_function: bool,
     pub parameters: Vec<Type>,
     pub return_type: Option<Type>,
+    /// Onde o símbolo foi definido; `None` para os built-ins
+    pub location: Option<Location>,
+    /// Valor de uma constante, avaliado em tempo de compilação
+    pub value: Option<Literal>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +48,11 @@ impl Scope {
         Ok(())
     }
 
+    /// Procura um símbolo apenas neste escopo, sem consultar os pais
+    pub fn resolve_local(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.get(name)
+    }
+
     pub fn resolve(&self, name: &str) -> Option<&Symbol> {
         if let Some(symbol) = self.symbols.get(name) {
             Some(symbol)
@@ -51,94 +62,418 @@ impl Scope {
             None
         }
     }
+
+    /// Nomes definidos diretamente neste escopo, sem os dos pais
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.symbols.keys().map(String::as_str)
+    }
+
+    /// Todos os símbolos visíveis a partir deste escopo, ordenados por nome.
+    /// Um nome sombreado aparece uma única vez, com o símbolo mais interno.
+    pub fn all_visible(&self) -> Vec<&Symbol> {
+        let mut visible: HashMap<&str, &Symbol> = HashMap::new();
+        let mut scope = Some(self);
+
+        while let Some(current) = scope {
+            for (name, symbol) in &current.symbols {
+                visible.entry(name.as_str()).or_insert(symbol);
+            }
+            scope = current.parent.as_deref();
+        }
+
+        let mut symbols: Vec<&Symbol> = visible.into_values().collect();
+        symbols.sort_by(|a, b| a.name.cmp(&b.name));
+        symbols
+    }
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tabela com o tipo resolvido de cada expressão analisada e o valor já
+/// dobrado dos inicializadores de `const` e das leituras de constantes.
+///
+/// As entradas são indexadas pelo endereço do nó na AST, portanto só são
+/// válidas enquanto o `Program` analisado não for modificado nem clonado.
+#[derive(Debug, Clone, Default)]
+pub struct ExpressionTypes {
+    types: HashMap<usize, Type>,
+    constants: HashMap<usize, Literal>,
+}
+
+impl ExpressionTypes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, expression: &Expression) -> Option<&Type> {
+        self.types.get(&Self::key(expression))
+    }
+
+    /// Valor calculado em tempo de compilação, se a expressão é o
+    /// inicializador de uma `const` ou lê uma
+    pub fn constant(&self, expression: &Expression) -> Option<&Literal> {
+        self.constants.get(&Self::key(expression))
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    fn insert(&mut self, expression: &Expression, expression_type: Type) {
+        self.types.insert(Self::key(expression), expression_type);
+    }
+
+    fn insert_constant(&mut self, expression: &Expression, value: Literal) {
+        self.constants.insert(Self::key(expression), value);
+    }
+
+    fn key(expression: &Expression) -> usize {
+        expression as *const Expression as usize
+    }
+}
+
+/// Aviso emitido pela análise semântica; não interrompe a compilação
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticWarning {
+    pub message: String,
+    pub location: Location,
+}
+
+impl fmt::Display for SemanticWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Aviso na linha {}, coluna {}: {}",
+            self.location.line, self.location.column, self.message
+        )
+    }
+}
+
+impl From<&SemanticWarning> for Diagnostic {
+    fn from(warning: &SemanticWarning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: warning.message.clone(),
+            line: Some(warning.location.line),
+            column: Some(warning.location.column),
+        }
+    }
+}
+
+impl Symbol {
+    /// Função pré-definida, sem posição no código fonte
+    pub fn builtin(name: &str, parameters: Vec<Type>, return_type: Type) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol_type: Type::Function {
+                parameters: parameters.clone(),
+                return_type: Box::new(return_type.clone()),
+            },
+            is_function: true,
+            parameters,
+            return_type: Some(return_type),
+            location: None,
+            value: None,
+        }
+    }
+}
+
+/// Tipo do resultado de cada operador binário para cada par de tipos dos
+/// operandos; combinações ausentes são erros de tipo
+#[derive(Debug, Clone, Default)]
+pub struct OperatorTable {
+    entries: HashMap<(BinaryOperator, Type, Type), Type>,
+}
+
+impl OperatorTable {
+    /// Tabela vazia; use `default_operators()` como base
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra o tipo do resultado de `left operator right`, substituindo
+    /// um registro anterior para a mesma combinação
+    pub fn register(&mut self, operator: BinaryOperator, left: Type, right: Type, result: Type) {
+        self.entries.insert((operator, left, right), result);
+    }
+
+    pub fn resolve(&self, operator: &BinaryOperator, left: &Type, right: &Type) -> Option<Type> {
+        self.entries.get(&(operator.clone(), left.clone(), right.clone())).cloned()
+    }
+}
+
+/// Combinações de operadores e tipos da linguagem. Uma nova combinação,
+/// como `+` entre strings, só precisa ser registrada aqui.
+pub fn default_operators() -> OperatorTable {
+    use BinaryOperator::*;
+
+    let mut table = OperatorTable::new();
+    let numeric = [
+        (Type::Int, Type::Int),
+        (Type::Int, Type::Float),
+        (Type::Float, Type::Int),
+        (Type::Float, Type::Float),
+    ];
+
+    // Aritmética: int com int continua int; com algum float, vira float
+    for operator in [Add, Subtract, Multiply, Divide] {
+        for (left, right) in numeric.clone() {
+            let result = if left == Type::Int && right == Type::Int { Type::Int } else { Type::Float };
+            table.register(operator.clone(), left, right, result);
+        }
+    }
+    table.register(Modulo, Type::Int, Type::Int, Type::Int);
+
+    // Igualdade entre tipos compatíveis; int só é promovido à direita
+    let equatable = [
+        (Type::Int, Type::Int),
+        (Type::Float, Type::Float),
+        (Type::Float, Type::Int),
+        (Type::Bool, Type::Bool),
+        (Type::Char, Type::Char),
+        (Type::String, Type::String),
+        (Type::Void, Type::Void),
+    ];
+    for operator in [Equal, NotEqual] {
+        for (left, right) in equatable.clone() {
+            table.register(operator.clone(), left, right, Type::Bool);
+        }
+    }
+
+    for operator in [LessThan, LessThanEqual, GreaterThan, GreaterThanEqual] {
+        for (left, right) in numeric.clone() {
+            table.register(operator.clone(), left, right, Type::Bool);
+        }
+        table.register(operator, Type::Char, Type::Char, Type::Bool);
+    }
+
+    for operator in [And, Or] {
+        table.register(operator, Type::Bool, Type::Bool, Type::Bool);
+    }
+
+    table
+}
+
+/// Funções disponíveis em todo programa quando o analisador é criado com
+/// `SemanticAnalyzer::new`
+pub fn default_builtins() -> Vec<Symbol> {
+    vec![
+        Symbol::builtin("print", vec![Type::String], Type::Void),
+        // println - sobrecargas para diferentes tipos
+        Symbol::builtin("println", vec![Type::String], Type::Void),
+        Symbol::builtin("println_int", vec![Type::Int], Type::Void),
+        Symbol::builtin("println_float", vec![Type::Float], Type::Void),
+        Symbol::builtin("println_bool", vec![Type::Bool], Type::Void),
+        // assert(bool): encerra o programa com código 1 se a condição for falsa
+        Symbol::builtin("assert", vec![Type::Bool], Type::Void),
+    ]
 }
 
 pub struct SemanticAnalyzer {
     current_scope: Scope,
+    builtins: Vec<Symbol>,
+    operators: OperatorTable,
     function_return_type: Option<Type>,
+    expression_types: ExpressionTypes,
+    warnings: Vec<SemanticWarning>,
+    warn_shadowing: bool,
+    warn_redundant_bool: bool,
+    warn_self_assignment: bool,
+    strict_types: bool,
+    /// Rótulos dos laços em análise, do mais externo ao mais interno
+    loop_labels: Vec<Option<String>>,
+    errors: Vec<CompilerError>,
+    recover: bool,
+}
+
+impl Default for SemanticAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SemanticAnalyzer {
     pub fn new() -> Self {
+        Self::with_builtins(default_builtins())
+    }
+
+    /// Cria um analisador cujas únicas funções pré-definidas são `builtins`;
+    /// use `default_builtins()` como base para apenas acrescentar funções
+    pub fn with_builtins(builtins: Vec<Symbol>) -> Self {
         Self {
             current_scope: Scope::new(),
+            builtins,
+            operators: default_operators(),
             function_return_type: None,
+            expression_types: ExpressionTypes::new(),
+            warnings: Vec::new(),
+            warn_shadowing: false,
+            warn_redundant_bool: false,
+            warn_self_assignment: false,
+            strict_types: false,
+            loop_labels: Vec::new(),
+            errors: Vec::new(),
+            recover: false,
         }
     }
 
+    /// Cria um analisador cujo escopo global já contém os símbolos de
+    /// `scope`, como o escopo extraído de uma análise anterior por
+    /// `take_scope` (útil para um REPL manter as definições entre entradas)
+    pub fn with_scope(scope: Scope) -> Self {
+        let mut analyzer = Self::new();
+        analyzer.current_scope = scope;
+        analyzer
+    }
+
+    /// Emite um aviso quando uma declaração sombreia um nome visível em um
+    /// escopo externo (desativado por padrão)
+    pub fn with_shadowing_warnings(mut self, enabled: bool) -> Self {
+        self.warn_shadowing = enabled;
+        self
+    }
+
+    /// Procura um símbolo visível no escopo atual; após `analyze`, o escopo
+    /// global com as funções e variáveis de nível superior
+    pub fn lookup(&self, name: &str) -> Option<&Symbol> {
+        self.current_scope.resolve(name)
+    }
+
+    /// Emite um aviso para comparações com um literal booleano, como
+    /// `x == true`, sugerindo `x` ou `!x` (desativado por padrão)
+    pub fn with_redundant_bool_warnings(mut self, enabled: bool) -> Self {
+        self.warn_redundant_bool = enabled;
+        self
+    }
+
+    /// Emite um aviso para atribuições de uma variável a ela mesma, como
+    /// `x = x;` (desativado por padrão)
+    pub fn with_self_assignment_warnings(mut self, enabled: bool) -> Self {
+        self.warn_self_assignment = enabled;
+        self
+    }
+
+    /// Exige conversão explícita (`as float`) onde um `int` seria aceito
+    /// como `float`, em declarações, atribuições, argumentos e retornos
+    /// (desativado por padrão)
+    pub fn with_strict_types(mut self, enabled: bool) -> Self {
+        self.strict_types = enabled;
+        self
+    }
+
+    /// Avisos emitidos durante a análise
+    pub fn warnings(&self) -> &[SemanticWarning] {
+        &self.warnings
+    }
+
+    /// Tipos resolvidos das expressões da última análise
+    pub fn expression_types(&self) -> &ExpressionTypes {
+        &self.expression_types
+    }
+
+    /// Extrai o escopo global após a análise, com as funções e variáveis de
+    /// nível superior definidas até aqui
+    pub fn take_scope(&mut self) -> Scope {
+        std::mem::take(&mut self.current_scope)
+    }
+
+    /// Extrai a tabela de tipos para uso nas fases seguintes
+    pub fn take_expression_types(&mut self) -> ExpressionTypes {
+        std::mem::take(&mut self.expression_types)
+    }
+
     pub fn analyze(&mut self, program: &Program) -> CompilerResult<()> {
         // Definir funções built-in
         self.define_builtins()?;
 
         // Analisar todas as declarações
-        for statement in &program.statements {
-            self.analyze_statement(statement)?;
+        self.analyze_statements(&program.statements)
+    }
+
+    /// Como `analyze`, mas continua após um erro na declaração seguinte e
+    /// retorna todos os erros encontrados
+    pub fn analyze_all(&mut self, program: &Program) -> Result<(), Vec<CompilerError>> {
+        self.errors.clear();
+        self.recover = true;
+        let result = self.analyze(program);
+        self.recover = false;
+
+        if let Err(error) = result {
+            self.errors.push(error);
+        }
+
+        let errors = std::mem::take(&mut self.errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Analisa uma sequência de declarações; em `analyze_all`, um erro é
+    /// registrado e a análise segue com a próxima declaração
+    fn analyze_statements(&mut self, statements: &[Statement]) -> CompilerResult<()> {
+        for statement in statements {
+            if let Err(error) = self.analyze_statement(statement) {
+                if !self.recover {
+                    return Err(error);
+                }
+                self.errors.push(error);
+            }
         }
 
         Ok(())
     }
 
-    fn define_builtins(&mut self) -> CompilerResult<()> {
-        // Função print
-        self.current_scope.define(Symbol {
-            name: "print".to_string(),
-            symbol_type: Type::Function {
-                parameters: vec![Type::String],
-                return_type: Box::new(Type::Void),
-            },
-            is_function: true,
-            parameters: vec![Type::String],
-            return_type: Some(Type::Void),
-        })?;
+    /// Verifica se o programa define o ponto de entrada chamado por `_start`:
+    /// uma função sem parâmetros que retorna `int` ou `void`
+    pub fn check_entry_point(&self, program: &Program, name: &str) -> CompilerResult<()> {
+        let entry = program.statements.iter().find_map(|statement| match statement {
+            Statement::Function(func) if func.name == name => Some(func),
+            _ => None,
+        });
 
-        // Função println - sobrecargas para diferentes tipos
-        // println(string)
-        self.current_scope.define(Symbol {
-            name: "println".to_string(),
-            symbol_type: Type::Function {
-                parameters: vec![Type::String],
-                return_type: Box::new(Type::Void),
-            },
-            is_function: true,
-            parameters: vec![Type::String],
-            return_type: Some(Type::Void),
+        let func = entry.ok_or_else(|| {
+            CompilerError::semantic(format!("Função '{}' não encontrada", name))
         })?;
 
-        // println(int)
-        self.current_scope.define(Symbol {
-            name: "println_int".to_string(),
-            symbol_type: Type::Function {
-                parameters: vec![Type::Int],
-                return_type: Box::new(Type::Void),
-            },
-            is_function: true,
-            parameters: vec![Type::Int],
-            return_type: Some(Type::Void),
-        })?;
+        if !func.parameters.is_empty() {
+            return Err(CompilerError::semantic_with_location(
+                format!("Função '{}' não deve receber parâmetros", name),
+                func.location.line,
+                func.location.column,
+            ));
+        }
 
-        // println(float)
-        self.current_scope.define(Symbol {
-            name: "println_float".to_string(),
-            symbol_type: Type::Function {
-                parameters: vec![Type::Float],
-                return_type: Box::new(Type::Void),
-            },
-            is_function: true,
-            parameters: vec![Type::Float],
-            return_type: Some(Type::Void),
-        })?;
+        if func.return_type != Type::Int && func.return_type != Type::Void {
+            return Err(CompilerError::type_error_with_location(
+                format!(
+                    "Função '{}' deve retornar int ou void, encontrado {}",
+                    name, func.return_type
+                ),
+                func.location.line,
+                func.location.column,
+            ));
+        }
 
-        // println(bool)
-        self.current_scope.define(Symbol {
-            name: "println_bool".to_string(),
-            symbol_type: Type::Function {
-                parameters: vec![Type::Bool],
-                return_type: Box::new(Type::Void),
-            },
-            is_function: true,
-            parameters: vec![Type::Bool],
-            return_type: Some(Type::Void),
-        })?;
+        Ok(())
+    }
+
+    fn define_builtins(&mut self) -> CompilerResult<()> {
+        // Um escopo herdado de uma análise anterior já tem os built-ins
+        for builtin in self.builtins.clone() {
+            if self.current_scope.resolve_local(&builtin.name).is_none() {
+                self.current_scope.define(builtin)?;
+            }
+        }
 
         Ok(())
     }
@@ -166,6 +501,13 @@ impl SemanticAnalyzer {
             Statement::Return(return_stmt) => {
                 self.analyze_return_statement(return_stmt)?;
             }
+            Statement::Empty(_) | Statement::Error(_) => {}
+            Statement::Break(break_stmt) => {
+                self.analyze_jump("break", break_stmt.label.as_deref(), &break_stmt.location)?;
+            }
+            Statement::Continue(continue_stmt) => {
+                self.analyze_jump("continue", continue_stmt.label.as_deref(), &continue_stmt.location)?;
+            }
             Statement::Block(block_stmt) => {
                 self.analyze_block_statement(block_stmt)?;
             }
@@ -174,8 +516,9 @@ impl SemanticAnalyzer {
     }
 
     fn analyze_declaration(&mut self, decl: &DeclarationStatement) -> CompilerResult<()> {
-        // Verificar se a variável já foi declarada
-        if self.current_scope.resolve(&decl.name).is_some() {
+        // Verificar se a variável já foi declarada neste escopo; declarar o
+        // mesmo nome em um bloco interno sombreia a definição externa
+        if self.current_scope.resolve_local(&decl.name).is_some() {
             return Err(CompilerError::semantic_with_location(
                 format!("Variável '{}' já foi declarada", decl.name),
                 decl.location.line,
@@ -183,31 +526,103 @@ impl SemanticAnalyzer {
             ));
         }
 
-        // Analisar inicializador se presente
-        if let Some(initializer) = &decl.initializer {
-            let init_type = self.analyze_expression(initializer)?;
-            if !self.types_compatible(&decl.var_type, &init_type) {
-                return Err(CompilerError::type_error_with_location(
-                    format!(
-                        "Tipo incompatível: esperado {}, encontrado {}",
-                        decl.var_type, init_type
+        if self.warn_shadowing {
+            // Built-ins não têm posição e não contam como sombreamento
+            let shadowed = self.current_scope.resolve(&decl.name).and_then(|symbol| symbol.location.as_ref());
+            if let Some(previous) = shadowed {
+                self.warnings.push(SemanticWarning {
+                    message: format!(
+                        "'{}' sombreia a declaração na linha {}, coluna {}",
+                        decl.name, previous.line, previous.column
                     ),
-                    decl.location.line,
-                    decl.location.column,
-                ));
+                    location: decl.location.clone(),
+                });
+            }
+        }
+
+        // Analisar inicializador se presente
+        let initializer_check = match &decl.initializer {
+            Some(initializer) => self.analyze_initializer(decl, initializer),
+            None if decl.var_type == Type::Inferred => Err(CompilerError::semantic_with_location(
+                format!(
+                    "Não é possível inferir o tipo de '{}': declare o tipo ou adicione um inicializador",
+                    decl.name
+                ),
+                decl.location.line,
+                decl.location.column,
+            )),
+            None => Ok(decl.var_type.clone()),
+        };
+
+        // Constantes só podem depender de literais e de outras constantes
+        let mut value = None;
+        if decl.is_const && initializer_check.is_ok() {
+            let initializer = decl.initializer.as_ref();
+            value = initializer.and_then(|init| self.constant_value(init));
+            match (initializer, &value) {
+                (Some(initializer), Some(folded)) => self.expression_types.insert_constant(initializer, folded.clone()),
+                _ => {
+                    return Err(CompilerError::semantic_with_location(
+                        format!("O inicializador da constante '{}' não é uma expressão constante", decl.name),
+                        decl.location.line,
+                        decl.location.column,
+                    ));
+                }
+            }
+            // Uma constante float iniciada com um int guarda o valor convertido
+            if let (Ok(Type::Float), Some(Literal::Integer(n))) = (&initializer_check, &value) {
+                value = Some(Literal::Float(*n as f64));
             }
         }
 
-        // Definir a variável no escopo atual
+        // Definir a variável no escopo atual mesmo com inicializador inválido,
+        // para que `analyze_all` não reporte os usos seguintes como não
+        // declarados; sem o tipo inferido, ela é tratada como `int`
+        let symbol_type = match &initializer_check {
+            Ok(var_type) => var_type.clone(),
+            Err(_) if decl.var_type == Type::Inferred => Type::Int,
+            Err(_) => decl.var_type.clone(),
+        };
         self.current_scope.define(Symbol {
             name: decl.name.clone(),
-            symbol_type: decl.var_type.clone(),
+            symbol_type,
             is_function: false,
             parameters: vec![],
             return_type: None,
+            location: Some(decl.location.clone()),
+            value,
         })?;
 
-        Ok(())
+        initializer_check.map(|_| ())
+    }
+
+    /// Verifica o inicializador e retorna o tipo da variável: o declarado ou,
+    /// se omitido, o do próprio inicializador
+    fn analyze_initializer(&mut self, decl: &DeclarationStatement, initializer: &Expression) -> CompilerResult<Type> {
+        let init_type = self.analyze_expression(initializer)?;
+        if decl.var_type == Type::Inferred {
+            if init_type == Type::Void {
+                return Err(CompilerError::type_error_with_location(
+                    format!("Não é possível inferir o tipo de '{}' a partir de uma expressão void", decl.name),
+                    decl.location.line,
+                    decl.location.column,
+                ));
+            }
+            return Ok(init_type);
+        }
+
+        if !self.types_compatible(&decl.var_type, &init_type) {
+            return Err(CompilerError::type_error_with_location(
+                format!(
+                    "Tipo incompatível: esperado {}, encontrado {}",
+                    decl.var_type, init_type
+                ),
+                decl.location.line,
+                decl.location.column,
+            ));
+        }
+
+        Ok(decl.var_type.clone())
     }
 
     fn analyze_assignment(&mut self, assign: &AssignmentStatement) -> CompilerResult<()> {
@@ -221,7 +636,7 @@ impl SemanticAnalyzer {
                 )
             })?;
             
-            (symbol.is_function, symbol.symbol_type.clone())
+            (symbol.is_function, symbol.symbol_type.clone(), symbol.value.is_some())
         };
 
         if symbol_info.0 {
@@ -232,6 +647,14 @@ impl SemanticAnalyzer {
             ));
         }
 
+        if symbol_info.2 {
+            return Err(CompilerError::semantic_with_location(
+                format!("Não é possível atribuir à constante '{}'", assign.target),
+                assign.location.line,
+                assign.location.column,
+            ));
+        }
+
         // Analisar o valor da atribuição
         let value_type = self.analyze_expression(&assign.value)?;
 
@@ -247,6 +670,7 @@ impl SemanticAnalyzer {
             ));
         }
 
+        self.check_self_assignment(&assign.target, &assign.value, &assign.location);
         Ok(())
     }
 
@@ -289,17 +713,270 @@ impl SemanticAnalyzer {
             ));
         }
 
+        // Um laço com condição sempre verdadeira só termina por `break` ou `return`
+        if Self::constant_bool(&while_stmt.condition) == Some(true)
+            && !Self::can_leave_loop(&while_stmt.body, while_stmt.label.as_deref())
+        {
+            self.warnings.push(SemanticWarning {
+                message: "laço provavelmente infinito: a condição é sempre verdadeira e o corpo não tem 'break' nem 'return'".to_string(),
+                location: while_stmt.location.clone(),
+            });
+        }
+
+        if let Some(label) = &while_stmt.label {
+            if self.loop_labels.iter().any(|outer| outer.as_ref() == Some(label)) {
+                return Err(CompilerError::semantic_with_location(
+                    format!("Rótulo de laço '{}' já está em uso por um laço externo", label),
+                    while_stmt.location.line,
+                    while_stmt.location.column,
+                ));
+            }
+        }
+
         // Analisar corpo do loop
-        self.analyze_statement(&while_stmt.body)?;
+        self.loop_labels.push(while_stmt.label.clone());
+        let result = self.analyze_statement(&while_stmt.body);
+        self.loop_labels.pop();
+        result?;
 
         Ok(())
     }
 
+    /// `break`/`continue` só valem dentro de um laço, e o rótulo, se houver,
+    /// precisa ser de um laço que os envolve
+    fn analyze_jump(&self, keyword: &str, label: Option<&str>, location: &Location) -> CompilerResult<()> {
+        if self.loop_labels.is_empty() {
+            return Err(CompilerError::semantic_with_location(
+                format!("'{}' fora de um laço", keyword),
+                location.line,
+                location.column,
+            ));
+        }
+
+        if let Some(label) = label {
+            if !self.loop_labels.iter().any(|outer| outer.as_deref() == Some(label)) {
+                return Err(CompilerError::semantic_with_location(
+                    format!("Rótulo de laço '{}' não definido", label),
+                    location.line,
+                    location.column,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifica se a execução nunca passa do comando: todos os caminhos
+    /// terminam em `return` ou ficam presos em um `while (true)` sem `break`
+    fn always_returns(statement: &Statement) -> bool {
+        match statement {
+            Statement::Return(_) => true,
+            Statement::Block(block) => block.statements.iter().any(Self::always_returns),
+            Statement::If(if_stmt) => {
+                Self::always_returns(&if_stmt.then_branch)
+                    && if_stmt.else_branch.as_deref().is_some_and(Self::always_returns)
+            }
+            Statement::While(while_stmt) => {
+                Self::constant_bool(&while_stmt.condition) == Some(true)
+                    && !Self::breaks_out(&while_stmt.body, while_stmt.label.as_deref(), true)
+            }
+            _ => false,
+        }
+    }
+
+    /// Verifica se há um `break` que sai do laço com rótulo `label`;
+    /// `direct` indica que ainda não se entrou em um laço interno, onde só
+    /// `break label` sai do laço externo
+    fn breaks_out(statement: &Statement, label: Option<&str>, direct: bool) -> bool {
+        match statement {
+            Statement::Break(break_stmt) => match &break_stmt.label {
+                None => direct,
+                Some(target) => Some(target.as_str()) == label,
+            },
+            Statement::If(if_stmt) => {
+                Self::breaks_out(&if_stmt.then_branch, label, direct)
+                    || if_stmt.else_branch.as_deref().is_some_and(|branch| Self::breaks_out(branch, label, direct))
+            }
+            Statement::Block(block) => block.statements.iter().any(|stmt| Self::breaks_out(stmt, label, direct)),
+            Statement::While(inner) => Self::breaks_out(&inner.body, label, false),
+            _ => false,
+        }
+    }
+
+    /// Verifica se o corpo de um laço contém um `break` para ele (e não para
+    /// um laço interno) ou um `return`
+    fn can_leave_loop(statement: &Statement, label: Option<&str>) -> bool {
+        match statement {
+            Statement::Break(_) | Statement::Return(_) => true,
+            Statement::If(if_stmt) => {
+                Self::can_leave_loop(&if_stmt.then_branch, label)
+                    || if_stmt.else_branch.as_deref().is_some_and(|branch| Self::can_leave_loop(branch, label))
+            }
+            Statement::Block(block) => block.statements.iter().any(|stmt| Self::can_leave_loop(stmt, label)),
+            Statement::While(inner) => Self::leaves_from_inner_loop(&inner.body, label),
+            _ => false,
+        }
+    }
+
+    /// Dentro de um laço interno, só `return` ou `break rotulo` para o laço
+    /// externo o encerram
+    fn leaves_from_inner_loop(statement: &Statement, label: Option<&str>) -> bool {
+        match statement {
+            Statement::Return(_) => true,
+            Statement::Break(break_stmt) => label.is_some() && break_stmt.label.as_deref() == label,
+            Statement::If(if_stmt) => {
+                Self::leaves_from_inner_loop(&if_stmt.then_branch, label)
+                    || if_stmt.else_branch.as_deref().is_some_and(|branch| Self::leaves_from_inner_loop(branch, label))
+            }
+            Statement::Block(block) => block.statements.iter().any(|stmt| Self::leaves_from_inner_loop(stmt, label)),
+            Statement::While(inner) => Self::leaves_from_inner_loop(&inner.body, label),
+            _ => false,
+        }
+    }
+
+    /// Avalia uma expressão formada por literais e constantes já definidas;
+    /// `None` se ela depende de valores de tempo de execução. A expressão já
+    /// passou pela verificação de tipos.
+    fn constant_value(&self, expression: &Expression) -> Option<Literal> {
+        match expression {
+            Expression::Literal(literal) => Some(literal.value.clone()),
+            Expression::Identifier(identifier) => self.current_scope.resolve(&identifier.name)?.value.clone(),
+            Expression::Unary(unary) => match (&unary.operator, self.constant_value(&unary.operand)?) {
+                (UnaryOperator::Minus, Literal::Integer(n)) => n.checked_neg().map(Literal::Integer),
+                (UnaryOperator::Minus, Literal::Float(f)) => Some(Literal::Float(-f)),
+                (UnaryOperator::Not, Literal::Boolean(b)) => Some(Literal::Boolean(!b)),
+                _ => None,
+            },
+            Expression::Binary(binary) => {
+                let left = self.constant_value(&binary.left)?;
+                let right = self.constant_value(&binary.right)?;
+                Self::fold_binary(&binary.operator, left, right)
+            }
+            _ => None,
+        }
+    }
+
+    fn fold_binary(operator: &BinaryOperator, left: Literal, right: Literal) -> Option<Literal> {
+        use BinaryOperator::*;
+
+        match (left, right) {
+            // Como na execução, um int operado com um float é promovido
+            (Literal::Integer(l), Literal::Float(r)) => Self::fold_binary(operator, Literal::Float(l as f64), Literal::Float(r)),
+            (Literal::Float(l), Literal::Integer(r)) => Self::fold_binary(operator, Literal::Float(l), Literal::Float(r as f64)),
+            (Literal::Integer(l), Literal::Integer(r)) => match operator {
+                Add => l.checked_add(r).map(Literal::Integer),
+                Subtract => l.checked_sub(r).map(Literal::Integer),
+                Multiply => l.checked_mul(r).map(Literal::Integer),
+                Divide => l.checked_div(r).map(Literal::Integer),
+                Modulo => l.checked_rem(r).map(Literal::Integer),
+                Equal => Some(Literal::Boolean(l == r)),
+                NotEqual => Some(Literal::Boolean(l != r)),
+                LessThan => Some(Literal::Boolean(l < r)),
+                LessThanEqual => Some(Literal::Boolean(l <= r)),
+                GreaterThan => Some(Literal::Boolean(l > r)),
+                GreaterThanEqual => Some(Literal::Boolean(l >= r)),
+                _ => None,
+            },
+            (Literal::Float(l), Literal::Float(r)) => match operator {
+                Add => Some(Literal::Float(l + r)),
+                Subtract => Some(Literal::Float(l - r)),
+                Multiply => Some(Literal::Float(l * r)),
+                Divide => Some(Literal::Float(l / r)),
+                Equal => Some(Literal::Boolean(l == r)),
+                NotEqual => Some(Literal::Boolean(l != r)),
+                LessThan => Some(Literal::Boolean(l < r)),
+                LessThanEqual => Some(Literal::Boolean(l <= r)),
+                GreaterThan => Some(Literal::Boolean(l > r)),
+                GreaterThanEqual => Some(Literal::Boolean(l >= r)),
+                _ => None,
+            },
+            (Literal::Boolean(l), Literal::Boolean(r)) => match operator {
+                And => Some(Literal::Boolean(l && r)),
+                Or => Some(Literal::Boolean(l || r)),
+                Equal => Some(Literal::Boolean(l == r)),
+                NotEqual => Some(Literal::Boolean(l != r)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Dobra expressões booleanas formadas apenas por constantes
+    fn constant_bool(expression: &Expression) -> Option<bool> {
+        match expression {
+            Expression::Literal(LiteralExpression { value: Literal::Boolean(b), .. }) => Some(*b),
+            Expression::Unary(unary) if unary.operator == UnaryOperator::Not => {
+                Self::constant_bool(&unary.operand).map(|b| !b)
+            }
+            Expression::Binary(binary) => match binary.operator {
+                BinaryOperator::And => Some(Self::constant_bool(&binary.left)? && Self::constant_bool(&binary.right)?),
+                BinaryOperator::Or => Some(Self::constant_bool(&binary.left)? || Self::constant_bool(&binary.right)?),
+                _ => {
+                    let left = Self::constant_int(&binary.left)?;
+                    let right = Self::constant_int(&binary.right)?;
+                    match binary.operator {
+                        BinaryOperator::Equal => Some(left == right),
+                        BinaryOperator::NotEqual => Some(left != right),
+                        BinaryOperator::LessThan => Some(left < right),
+                        BinaryOperator::LessThanEqual => Some(left <= right),
+                        BinaryOperator::GreaterThan => Some(left > right),
+                        BinaryOperator::GreaterThanEqual => Some(left >= right),
+                        _ => None,
+                    }
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Dobra expressões inteiras formadas apenas por constantes
+    fn constant_int(expression: &Expression) -> Option<i64> {
+        match expression {
+            Expression::Literal(LiteralExpression { value: Literal::Integer(n), .. }) => Some(*n),
+            Expression::Unary(unary) if unary.operator == UnaryOperator::Minus => {
+                Self::constant_int(&unary.operand)?.checked_neg()
+            }
+            Expression::Binary(binary) => {
+                let left = Self::constant_int(&binary.left)?;
+                let right = Self::constant_int(&binary.right)?;
+                match binary.operator {
+                    BinaryOperator::Add => left.checked_add(right),
+                    BinaryOperator::Subtract => left.checked_sub(right),
+                    BinaryOperator::Multiply => left.checked_mul(right),
+                    BinaryOperator::Divide => left.checked_div(right),
+                    BinaryOperator::Modulo => left.checked_rem(right),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     fn analyze_function_declaration(&mut self, func: &FunctionStatement) -> CompilerResult<()> {
+        // Funções só podem ser definidas no nível superior: não há captura
+        // de variáveis e o código gerado ficaria no meio da função externa
+        if self.function_return_type.is_some() {
+            return Err(CompilerError::semantic_with_location(
+                format!(
+                    "funções aninhadas não são suportadas; declare '{}' no nível superior",
+                    func.name
+                ),
+                func.location.line,
+                func.location.column,
+            ));
+        }
+
         // Verificar se a função já foi declarada
-        if self.current_scope.resolve(&func.name).is_some() {
+        if let Some(existing) = self.current_scope.resolve(&func.name) {
+            let message = match &existing.location {
+                Some(previous) => format!(
+                    "função '{}' redefinida (definição anterior na linha {})",
+                    func.name, previous.line
+                ),
+                None => format!("Função '{}' já foi declarada", func.name),
+            };
             return Err(CompilerError::semantic_with_location(
-                format!("Função '{}' já foi declarada", func.name),
+                message,
                 func.location.line,
                 func.location.column,
             ));
@@ -316,34 +993,76 @@ impl SemanticAnalyzer {
             is_function: true,
             parameters: param_types,
             return_type: Some(func.return_type.clone()),
+            location: Some(func.location.clone()),
+            value: None,
         })?;
 
-        // Criar novo escopo para o corpo da função
-        let mut function_scope = Scope::with_parent(self.current_scope.clone());
+        // Novo escopo com os parâmetros para o corpo da função
+        self.enter_scope();
+        let old_return_type = self.function_return_type.replace(func.return_type.clone());
+
+        let result = self.analyze_function_body(func);
+
+        // Restaurar escopo anterior
+        self.exit_scope();
+        self.function_return_type = old_return_type;
+        result?;
+
+        // Uma função com valor de retorno não pode chegar ao fim do corpo:
+        // o código gerado retornaria o que estivesse em rax. Funções `void`,
+        // mesmo vazias, retornam implicitamente.
+        if func.return_type != Type::Void && !func.body.statements.iter().any(Self::always_returns) {
+            let message = if func.body.statements.is_empty() {
+                format!("Função '{}' tem o corpo vazio, mas deve retornar {}", func.name, func.return_type)
+            } else {
+                format!("Função '{}' deve retornar {} em todos os caminhos", func.name, func.return_type)
+            };
+            return Err(CompilerError::semantic_with_location(
+                message,
+                func.location.line,
+                func.location.column,
+            ));
+        }
+
+        Ok(())
+    }
 
-        // Adicionar parâmetros ao escopo da função
+    fn analyze_function_body(&mut self, func: &FunctionStatement) -> CompilerResult<()> {
         for param in &func.parameters {
-            function_scope.define(Symbol {
+            if self.current_scope.resolve_local(&param.name).is_some() {
+                return Err(CompilerError::semantic_with_location(
+                    format!("parâmetro '{}' duplicado", param.name),
+                    param.location.line,
+                    param.location.column,
+                ));
+            }
+
+            self.current_scope.define(Symbol {
                 name: param.name.clone(),
                 symbol_type: param.param_type.clone(),
                 is_function: false,
                 parameters: vec![],
                 return_type: None,
+                location: Some(param.location.clone()),
+                value: None,
             })?;
         }
 
-        // Analisar corpo da função
-        let old_scope = std::mem::replace(&mut self.current_scope, function_scope);
-        let old_return_type = self.function_return_type.take();
-        self.function_return_type = Some(func.return_type.clone());
-
-        self.analyze_block_statement(&func.body)?;
+        self.analyze_block_statement(&func.body)
+    }
 
-        // Restaurar escopo anterior
-        self.current_scope = old_scope;
-        self.function_return_type = old_return_type;
+    /// Entra em um escopo filho do atual. O escopo atual é movido para
+    /// dentro do filho, sem cópia, e volta a ser o atual em `exit_scope`.
+    fn enter_scope(&mut self) {
+        let parent = std::mem::take(&mut self.current_scope);
+        self.current_scope = Scope::with_parent(parent);
+    }
 
-        Ok(())
+    /// Sai do escopo atual, descartando seus símbolos
+    fn exit_scope(&mut self) {
+        if let Some(parent) = self.current_scope.parent.take() {
+            self.current_scope = *parent;
+        }
     }
 
     fn analyze_return_statement(&mut self, return_stmt: &ReturnStatement) -> CompilerResult<()> {
@@ -388,22 +1107,52 @@ impl SemanticAnalyzer {
 
     fn analyze_block_statement(&mut self, block: &BlockStatement) -> CompilerResult<()> {
         // Criar novo escopo para o bloco
-        let block_scope = Scope::with_parent(self.current_scope.clone());
-        let old_scope = std::mem::replace(&mut self.current_scope, block_scope);
+        self.enter_scope();
 
         // Analisar todas as declarações no bloco
-        for statement in &block.statements {
-            self.analyze_statement(statement)?;
+        let result = self.analyze_statements(&block.statements);
+        self.exit_scope();
+        result?;
+
+        // Declarações após um `return` nunca são executadas
+        let first_return = block
+            .statements
+            .iter()
+            .position(|statement| matches!(statement, Statement::Return(_)));
+        let unreachable = first_return.and_then(|index| {
+            block.statements[index + 1..]
+                .iter()
+                .find(|statement| !matches!(statement, Statement::Empty(_)))
+        });
+        if let Some(unreachable) = unreachable {
+            self.warnings.push(SemanticWarning {
+                message: "código inalcançável após 'return'".to_string(),
+                location: unreachable.location().clone(),
+            });
         }
 
-        // Restaurar escopo anterior
-        self.current_scope = old_scope;
-
         Ok(())
     }
 
     fn analyze_expression(&mut self, expression: &Expression) -> CompilerResult<Type> {
+        let expression_type = self.resolve_expression_type(expression)?;
+        self.expression_types.insert(expression, expression_type.clone());
+        if let Expression::Identifier(identifier) = expression {
+            if let Some(value) = self.current_scope.resolve(&identifier.name).and_then(|symbol| symbol.value.clone()) {
+                self.expression_types.insert_constant(expression, value);
+            }
+        }
+        Ok(expression_type)
+    }
+
+    fn resolve_expression_type(&mut self, expression: &Expression) -> CompilerResult<Type> {
         match expression {
+            // Sem tipo possível: o trecho original nem chegou a ser analisado
+            Expression::Error(error) => Err(CompilerError::semantic_with_location(
+                "expressão com erro de sintaxe",
+                error.location.line,
+                error.location.column,
+            )),
             Expression::Literal(literal_expr) => {
                 Ok(self.literal_type(&literal_expr.value))
             }
@@ -426,93 +1175,140 @@ impl SemanticAnalyzer {
             Expression::Call(call_expr) => {
                 self.analyze_call_expression(call_expr)
             }
+            Expression::IndirectCall(call_expr) => {
+                self.analyze_indirect_call_expression(call_expr)
+            }
             Expression::Assignment(assign_expr) => {
                 self.analyze_assignment_expression(assign_expr)
             }
+            Expression::Cast(cast_expr) => {
+                self.analyze_cast_expression(cast_expr)
+            }
         }
     }
 
     fn analyze_binary_expression(&mut self, binary: &BinaryExpression) -> CompilerResult<Type> {
+        // `a < b < c` seria analisado como `(a < b) < c`, comparando bool
+        // com número; rejeitar com uma mensagem mais útil
+        if Self::is_relational(&binary.operator)
+            && [&binary.left, &binary.right].iter().any(|operand| {
+                matches!(operand.as_ref(), Expression::Binary(inner) if Self::is_relational(&inner.operator))
+            })
+        {
+            return Err(CompilerError::semantic_with_location(
+                "encadeamento de comparações não é suportado; use &&".to_string(),
+                binary.location.line,
+                binary.location.column,
+            ));
+        }
+
         let left_type = self.analyze_expression(&binary.left)?;
         let right_type = self.analyze_expression(&binary.right)?;
 
-        match &binary.operator {
+        // Funções não cabem na tabela: são comparáveis quando as assinaturas
+        // são compatíveis
+        let resolved = self.operators.resolve(&binary.operator, &left_type, &right_type).or_else(|| {
+            let comparable_functions = matches!(binary.operator, BinaryOperator::Equal | BinaryOperator::NotEqual)
+                && matches!(left_type, Type::Function { .. })
+                && self.types_compatible(&left_type, &right_type);
+            comparable_functions.then_some(Type::Bool)
+        });
+        if let Some(result) = resolved {
+            if self.warn_redundant_bool {
+                self.check_redundant_bool_comparison(binary);
+            }
+            return Ok(result);
+        }
+
+        // `main + 1`, `main == 1`: nome de função usado como número
+        let function_operand = [&binary.left, &binary.right].into_iter().find_map(|operand| match operand.as_ref() {
+            Expression::Identifier(identifier) => self
+                .current_scope
+                .resolve(&identifier.name)
+                .filter(|symbol| symbol.is_function)
+                .map(|symbol| symbol.name.clone()),
+            _ => None,
+        });
+        if let Some(name) = function_operand {
+            return Err(CompilerError::type_error_with_location(
+                format!("'{}' é uma função e não pode ser usada como valor", name),
+                binary.location.line,
+                binary.location.column,
+            ));
+        }
+
+        let message = match &binary.operator {
             BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide => {
-                if left_type == Type::Int && right_type == Type::Int {
-                    Ok(Type::Int)
-                } else if (left_type == Type::Int || left_type == Type::Float) && 
-                          (right_type == Type::Int || right_type == Type::Float) {
-                    Ok(Type::Float)
-                } else {
-                    Err(CompilerError::type_error_with_location(
-                        format!(
-                            "Operação {} não suportada entre {} e {}",
-                            binary.operator, left_type, right_type
-                        ),
-                        binary.location.line,
-                        binary.location.column,
-                    ))
-                }
+                format!("Operação {} não suportada entre {} e {}", binary.operator, left_type, right_type)
             }
             BinaryOperator::Equal | BinaryOperator::NotEqual => {
-                if self.types_compatible(&left_type, &right_type) {
-                    Ok(Type::Bool)
+                let numeric = |ty: &Type| matches!(ty, Type::Int | Type::Float);
+                if (left_type == Type::String && numeric(&right_type))
+                    || (numeric(&left_type) && right_type == Type::String)
+                {
+                    format!("não é possível comparar {} com {}", left_type, right_type)
                 } else {
-                    Err(CompilerError::type_error_with_location(
-                        format!(
-                            "Comparação {} não suportada entre {} e {}",
-                            binary.operator, left_type, right_type
-                        ),
-                        binary.location.line,
-                        binary.location.column,
-                    ))
+                    format!("Comparação {} não suportada entre {} e {}", binary.operator, left_type, right_type)
                 }
             }
-            BinaryOperator::LessThan | BinaryOperator::LessThanEqual | 
+            BinaryOperator::LessThan | BinaryOperator::LessThanEqual |
             BinaryOperator::GreaterThan | BinaryOperator::GreaterThanEqual => {
-                if (left_type == Type::Int || left_type == Type::Float) && 
-                   (right_type == Type::Int || right_type == Type::Float) {
-                    Ok(Type::Bool)
-                } else {
-                    Err(CompilerError::type_error_with_location(
-                        format!(
-                            "Comparação {} não suportada entre {} e {}",
-                            binary.operator, left_type, right_type
-                        ),
-                        binary.location.line,
-                        binary.location.column,
-                    ))
-                }
+                format!("Comparação {} não suportada entre {} e {}", binary.operator, left_type, right_type)
             }
             BinaryOperator::And | BinaryOperator::Or => {
-                if left_type == Type::Bool && right_type == Type::Bool {
-                    Ok(Type::Bool)
-                } else {
-                    Err(CompilerError::type_error_with_location(
-                        format!(
-                            "Operação lógica {} não suportada entre {} e {}",
-                            binary.operator, left_type, right_type
-                        ),
-                        binary.location.line,
-                        binary.location.column,
-                    ))
-                }
+                format!("Operação lógica {} não suportada entre {} e {}", binary.operator, left_type, right_type)
             }
             BinaryOperator::Modulo => {
-                if left_type == Type::Int && right_type == Type::Int {
-                    Ok(Type::Int)
-                } else {
-                    Err(CompilerError::type_error_with_location(
-                        format!(
-                            "Operação módulo não suportada entre {} e {}",
-                            left_type, right_type
-                        ),
-                        binary.location.line,
-                        binary.location.column,
-                    ))
-                }
+                // Não há `fmod` no runtime: `%` só existe para inteiros
+                format!(
+                    "operação módulo requer operandos inteiros, encontrado {} e {}",
+                    left_type, right_type
+                )
             }
-        }
+        };
+
+        Err(CompilerError::type_error_with_location(
+            message,
+            binary.location.line,
+            binary.location.column,
+        ))
+    }
+
+    /// Avisa sobre `x == true`, `x != false` e semelhantes; os tipos já
+    /// foram verificados, então o outro lado também é bool
+    fn check_redundant_bool_comparison(&mut self, binary: &BinaryExpression) {
+        let negated = match binary.operator {
+            BinaryOperator::Equal => false,
+            BinaryOperator::NotEqual => true,
+            _ => return,
+        };
+
+        let literal = |expression: &Expression| match expression {
+            Expression::Literal(LiteralExpression { value: Literal::Boolean(b), .. }) => Some(*b),
+            _ => None,
+        };
+        let (operand, value) = match (literal(&binary.left), literal(&binary.right)) {
+            (None, Some(value)) => (&binary.left, value),
+            (Some(value), None) => (&binary.right, value),
+            _ => return,
+        };
+
+        let text = describe_expression(operand);
+        let suggestion = if value != negated {
+            text
+        } else if matches!(operand.as_ref(), Expression::Identifier(_) | Expression::Call(_) | Expression::Literal(_)) {
+            format!("!{}", text)
+        } else {
+            format!("!({})", text)
+        };
+
+        self.warnings.push(SemanticWarning {
+            message: format!(
+                "comparação redundante com '{}'; use '{}'",
+                value, suggestion
+            ),
+            location: binary.location.clone(),
+        });
     }
 
     fn analyze_unary_expression(&mut self, unary: &UnaryExpression) -> CompilerResult<Type> {
@@ -555,6 +1351,27 @@ impl SemanticAnalyzer {
         }
     }
 
+    fn analyze_cast_expression(&mut self, cast: &CastExpression) -> CompilerResult<Type> {
+        let source_type = self.analyze_expression(&cast.expression)?;
+
+        // Conversões permitidas: int <-> float, bool -> int e a identidade
+        let allowed = source_type == cast.target_type
+            || matches!(
+                (&source_type, &cast.target_type),
+                (Type::Int, Type::Float) | (Type::Float, Type::Int) | (Type::Bool, Type::Int)
+            );
+
+        if !allowed {
+            return Err(CompilerError::type_error_with_location(
+                format!("Conversão de {} para {} não é permitida", source_type, cast.target_type),
+                cast.location.line,
+                cast.location.column,
+            ));
+        }
+
+        Ok(cast.target_type.clone())
+    }
+
     fn analyze_call_expression(&mut self, call: &CallExpression) -> CompilerResult<Type> {
         let symbol_info = {
             let symbol = self.current_scope.resolve(&call.function).ok_or_else(|| {
@@ -565,50 +1382,85 @@ impl SemanticAnalyzer {
                 )
             })?;
 
-            (symbol.is_function, symbol.parameters.clone(), symbol.return_type.clone())
+            if symbol.is_function {
+                Ok((symbol.parameters.clone(), symbol.return_type.clone().unwrap_or(Type::Void)))
+            } else if let Type::Function { parameters, return_type } = &symbol.symbol_type {
+                // Variável que guarda uma função
+                Ok((parameters.clone(), return_type.as_ref().clone()))
+            } else {
+                Err(symbol.symbol_type.clone())
+            }
         };
 
-        if !symbol_info.0 {
-            return Err(CompilerError::semantic_with_location(
-                format!("'{}' não é uma função", call.function),
+        let (parameters, return_type) = symbol_info.map_err(|symbol_type| {
+            CompilerError::semantic_with_location(
+                format!("'{}' é do tipo {} e não pode ser chamada", call.function, symbol_type),
+                call.location.line,
+                call.location.column,
+            )
+        })?;
+
+        self.check_call_arguments(&call.function, &parameters, &call.arguments, &call.location)?;
+
+        Ok(return_type)
+    }
+
+    fn analyze_indirect_call_expression(&mut self, call: &IndirectCallExpression) -> CompilerResult<Type> {
+        let callee_type = self.analyze_expression(&call.callee)?;
+
+        let Type::Function { parameters, return_type } = callee_type else {
+            return Err(CompilerError::type_error_with_location(
+                format!("Expressão do tipo {} não pode ser chamada", callee_type),
                 call.location.line,
                 call.location.column,
             ));
-        }
+        };
+
+        self.check_call_arguments("<expressão>", &parameters, &call.arguments, &call.location)?;
 
+        Ok(*return_type)
+    }
+
+    fn check_call_arguments(
+        &mut self,
+        function: &str,
+        parameters: &[Type],
+        arguments: &[Expression],
+        location: &Location,
+    ) -> CompilerResult<()> {
         // Verificar número de argumentos
-        if call.arguments.len() != symbol_info.1.len() {
+        if arguments.len() != parameters.len() {
             return Err(CompilerError::semantic_with_location(
                 format!(
                     "Função '{}' espera {} argumentos, mas {} foram fornecidos",
-                    call.function,
-                    symbol_info.1.len(),
-                    call.arguments.len()
+                    function,
+                    parameters.len(),
+                    arguments.len()
                 ),
-                call.location.line,
-                call.location.column,
+                location.line,
+                location.column,
             ));
         }
 
         // Verificar tipos dos argumentos
-        for (i, (arg, expected_type)) in call.arguments.iter().zip(symbol_info.1.iter()).enumerate() {
+        for (i, (arg, expected_type)) in arguments.iter().zip(parameters.iter()).enumerate() {
             let arg_type = self.analyze_expression(arg)?;
             if !self.types_compatible(expected_type, &arg_type) {
                 return Err(CompilerError::type_error_with_location(
                     format!(
                         "Argumento {} da função '{}': esperado {}, encontrado {}",
                         i + 1,
-                        call.function,
+                        function,
                         expected_type,
                         arg_type
                     ),
-                    call.location.line,
-                    call.location.column,
+                    location.line,
+                    location.column,
                 ));
             }
         }
 
-        Ok(symbol_info.2.unwrap_or(Type::Void))
+        Ok(())
     }
 
     fn analyze_assignment_expression(&mut self, assign: &AssignmentExpression) -> CompilerResult<Type> {
@@ -620,6 +1472,13 @@ impl SemanticAnalyzer {
                     assign.location.column,
                 )
             })?;
+            if symbol.value.is_some() {
+                return Err(CompilerError::semantic_with_location(
+                    format!("Não é possível atribuir à constante '{}'", assign.target),
+                    assign.location.line,
+                    assign.location.column,
+                ));
+            }
             symbol.symbol_type.clone()
         };
 
@@ -636,14 +1495,40 @@ impl SemanticAnalyzer {
             ));
         }
 
+        self.check_self_assignment(&assign.target, &assign.value, &assign.location);
         Ok(symbol_type)
     }
 
+    /// Avisa sobre `x = x`, que não tem efeito e costuma ser um engano
+    fn check_self_assignment(&mut self, target: &str, value: &Expression, location: &Location) {
+        if !self.warn_self_assignment {
+            return;
+        }
+
+        if matches!(value, Expression::Identifier(identifier) if identifier.name == target) {
+            self.warnings.push(SemanticWarning {
+                message: format!("atribuição de '{}' a si mesma não tem efeito", target),
+                location: location.clone(),
+            });
+        }
+    }
+
+    fn is_relational(operator: &BinaryOperator) -> bool {
+        matches!(
+            operator,
+            BinaryOperator::LessThan
+                | BinaryOperator::LessThanEqual
+                | BinaryOperator::GreaterThan
+                | BinaryOperator::GreaterThanEqual
+        )
+    }
+
     fn literal_type(&self, literal: &Literal) -> Type {
         match literal {
             Literal::Integer(_) => Type::Int,
             Literal::Float(_) => Type::Float,
             Literal::Boolean(_) => Type::Bool,
+            Literal::Char(_) => Type::Char,
             Literal::String(_) => Type::String,
         }
     }
@@ -652,22 +1537,15 @@ impl SemanticAnalyzer {
         match (expected, actual) {
             (Type::Int, Type::Int) => true,
             (Type::Float, Type::Float) => true,
-            (Type::Float, Type::Int) => true, // Int pode ser convertido para Float
+            // Int pode ser convertido para Float, exceto no modo estrito
+            (Type::Float, Type::Int) => !self.strict_types,
             (Type::Bool, Type::Bool) => true,
+            (Type::Char, Type::Char) => true,
             (Type::String, Type::String) => true,
             (Type::Void, Type::Void) => true,
-            (Type::Function { parameters: p1, return_type: r1 }, 
-             Type::Function { parameters: p2, return_type: r2 }) => {
-                if p1.len() != p2.len() {
-                    return false;
-                }
-                for (t1, t2) in p1.iter().zip(p2.iter()) {
-                    if !self.types_compatible(t1, t2) {
-                        return false;
-                    }
-                }
-                self.types_compatible(r1, r2)
-            }
+            // Não há conversão ao chamar por um ponteiro de função: parâmetros
+            // e retorno precisam ser exatamente iguais
+            (expected @ Type::Function { .. }, actual @ Type::Function { .. }) => expected == actual,
             _ => false,
         }
     }
@@ -1,5 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use crate::ast::{walk_expression_mut, VisitorMut};
+use crate::error::{CompilerError, CompilerResult};
+use crate::lexer::{Lexer, Token};
 
 /// Estrutura para armazenar estatísticas do compilador
 #[derive(Debug, Default)]
@@ -12,6 +18,13 @@ pub struct CompilerStats {
     pub errors_found: usize,
     pub warnings_found: usize,
     pub compilation_time_ms: u64,
+    /// Tempo gasto em cada fase da última compilação
+    pub lexing_time: Duration,
+    pub parsing_time: Duration,
+    pub analysis_time: Duration,
+    pub optimization_time: Duration,
+    pub codegen_time: Duration,
+    pub total_time: Duration,
 }
 
 impl CompilerStats {
@@ -32,8 +45,68 @@ impl CompilerStats {
         println!("Avisos encontrados: {}", self.warnings_found);
         println!("Tempo de compilação: {}ms", self.compilation_time_ms);
     }
+
+    /// Imprime o tempo de cada fase e sua fração do tempo total
+    pub fn print_time_report(&self) {
+        let total = self.total_time.as_secs_f64();
+        let phases = [
+            ("Análise léxica", self.lexing_time),
+            ("Análise sintática", self.parsing_time),
+            ("Análise semântica", self.analysis_time),
+            ("Otimização", self.optimization_time),
+            ("Geração de código", self.codegen_time),
+        ];
+
+        println!("=== Tempo por Fase ===");
+        for (phase, time) in phases {
+            let percent = if total > 0.0 { time.as_secs_f64() / total * 100.0 } else { 0.0 };
+            println!("{:<20} {:>10.3}ms ({:>5.1}%)", phase, time.as_secs_f64() * 1000.0, percent);
+        }
+        println!("{:<20} {:>10.3}ms", "Total", total * 1000.0);
+    }
+
+    /// Como o `Display`, mas com os números agrupados de três em três
+    /// (`1,234,567`) e o tempo em segundos a partir de 1s (`2.5s`)
+    pub fn format_human(&self) -> String {
+        format!(
+            "Estatísticas: {} linhas, {} tokens, {} nós AST, {} funções, {} variáveis, {} erros, {} avisos, {}",
+            group_digits(self.lines_processed),
+            group_digits(self.tokens_generated),
+            group_digits(self.ast_nodes),
+            group_digits(self.functions_defined),
+            group_digits(self.variables_declared),
+            group_digits(self.errors_found),
+            group_digits(self.warnings_found),
+            human_duration(self.compilation_time_ms)
+        )
+    }
+}
+
+/// Dígitos separados em grupos de três por vírgulas
+fn group_digits(value: usize) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
 }
 
+/// Milissegundos abaixo de 1s; acima, segundos com até duas casas
+fn human_duration(milliseconds: u64) -> String {
+    if milliseconds < 1000 {
+        return format!("{}ms", milliseconds);
+    }
+    let seconds = format!("{:.2}", milliseconds as f64 / 1000.0);
+    format!("{}s", seconds.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Maior nível de otimização suportado
+pub const MAX_OPTIMIZATION_LEVEL: u8 = 3;
+
 /// Estrutura para configurações do compilador
 #[derive(Debug, Clone)]
 pub struct CompilerConfig {
@@ -44,14 +117,71 @@ pub struct CompilerConfig {
     pub _warnings_as_errors: bool,
     pub _target_architecture: String,
     pub _output_format: OutputFormat,
+    pub int_width: IntWidth,
+    pub calling_convention: CallingConvention,
+    /// Avisar quando uma declaração sombreia um nome de escopo externo
+    pub warn_shadowing: bool,
+    /// Avisar sobre comparações com `true`/`false`, como `x == true`
+    pub warn_redundant_bool: bool,
+    /// Avisar sobre atribuições de uma variável a ela mesma, como `x = x`
+    pub warn_self_assignment: bool,
+    /// Comentar o assembly com a linha do código fonte de cada comando
+    pub annotate_assembly: bool,
+    /// Não converter `int` em `float` implicitamente; exige `as float`
+    pub strict_types: bool,
+    /// Gerar código independente de posição, para ligar como PIE
+    pub pie: bool,
+    /// Função chamada por `_start`; `None` gera um objeto de biblioteca,
+    /// sem `_start` e com as funções exportadas
+    pub entry_point: Option<String>,
+    /// Máximo de erros de sintaxe reportados por
+    /// `Compiler::compile_str_with_diagnostics`
+    pub max_errors: usize,
+}
+
+/// Largura dos inteiros no código gerado. `Type::Int` tem a mesma semântica
+/// nos dois casos; muda apenas o tamanho dos registradores e das variáveis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntWidth {
+    Bits32,
+    #[default]
+    Bits64,
+}
+
+/// Convenção de chamada usada nas funções geradas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CallingConvention {
+    /// Linux/macOS: argumentos em rdi, rsi, rdx, rcx, r8 e r9
+    #[default]
+    SystemV,
+    /// Windows x64: argumentos em rcx, rdx, r8 e r9, com 32 bytes de shadow space
+    Windows,
+}
+
+impl CallingConvention {
+    /// Registradores usados para os primeiros argumentos, em ordem
+    pub fn argument_registers(&self) -> &'static [&'static str] {
+        match self {
+            CallingConvention::SystemV => &["rdi", "rsi", "rdx", "rcx", "r8", "r9"],
+            CallingConvention::Windows => &["rcx", "rdx", "r8", "r9"],
+        }
+    }
+
+    /// Bytes que o chamador reserva acima do endereço de retorno para o chamado
+    pub fn shadow_space(&self) -> usize {
+        match self {
+            CallingConvention::SystemV => 0,
+            CallingConvention::Windows => 32,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum OutputFormat {
     Assembly,
-    #[allow(dead_code)]
+    /// Arquivo objeto montado com `nasm`
     Object,
-    #[allow(dead_code)]
+    /// Ainda não suportado: exige ligar o objeto
     Executable,
 }
 
@@ -65,56 +195,392 @@ impl Default for CompilerConfig {
             _warnings_as_errors: false,
             _target_architecture: "x86_64".to_string(),
             _output_format: OutputFormat::Assembly,
+            int_width: IntWidth::default(),
+            calling_convention: CallingConvention::default(),
+            warn_shadowing: false,
+            warn_redundant_bool: false,
+            warn_self_assignment: false,
+            annotate_assembly: false,
+            strict_types: false,
+            pie: false,
+            entry_point: Some("main".to_string()),
+            max_errors: crate::parser::DEFAULT_MAX_ERRORS,
+        }
+    }
+}
+
+impl CompilerConfig {
+    /// Verifica se os valores da configuração são suportados
+    pub fn validate(&self) -> CompilerResult<()> {
+        if self._optimization_level > MAX_OPTIMIZATION_LEVEL {
+            return Err(CompilerError::config(format!(
+                "Nível de otimização inválido: {} (use 0-{})",
+                self._optimization_level, MAX_OPTIMIZATION_LEVEL
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Opções de formatação usadas por `SourceFormatter`
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Número de espaços por nível de indentação
+    pub indent_width: usize,
+    /// Indentar com tabulações em vez de espaços
+    pub use_tabs: bool,
+    /// Linhas mais longas são quebradas após operadores ou vírgulas
+    pub max_line_length: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            use_tabs: false,
+            max_line_length: 80,
         }
     }
 }
 
+/// Montador usado para gerar arquivos objeto
+pub const ASSEMBLER: &str = "nasm";
+
+/// Verifica se o montador está instalado e pode ser executado
+pub fn assembler_available() -> bool {
+    Command::new(ASSEMBLER)
+        .arg("-v")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Monta um arquivo assembly com `nasm -f elf64`, gerando o arquivo objeto
+/// em `object_path`
+pub fn assemble(assembly_path: &Path, object_path: &Path) -> CompilerResult<()> {
+    let output = Command::new(ASSEMBLER)
+        .arg("-f")
+        .arg("elf64")
+        .arg("-o")
+        .arg(object_path)
+        .arg(assembly_path)
+        .output()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                CompilerError::assembler(format!("'{}' não encontrado no PATH", ASSEMBLER))
+            } else {
+                CompilerError::assembler(format!("falha ao executar '{}': {}", ASSEMBLER, e))
+            }
+        })?;
+
+    if !output.status.success() {
+        return Err(CompilerError::assembler(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Utilitário para formatação de código fonte
 #[allow(dead_code)]
 pub struct SourceFormatter {
-    indent_size: usize,
-    _max_line_length: usize,
+    options: FormatOptions,
+}
+
+impl Default for SourceFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SourceFormatter {
     #[allow(dead_code)]
     pub fn new() -> Self {
-        Self {
-            indent_size: 4,
-            _max_line_length: 80,
-        }
+        Self::with_options(FormatOptions::default())
+    }
+
+    pub fn with_options(options: FormatOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn options(&self) -> &FormatOptions {
+        &self.options
     }
 
+    /// Reformata o código fonte: reindenta pelos blocos e normaliza o
+    /// espaçamento entre tokens (operadores, vírgulas, `:` em declarações).
+    /// Comentários de linha são preservados; linhas com comentários de bloco
+    /// ou que não puderem ser tokenizadas são apenas reindentadas. As linhas
+    /// que continuam uma string `"""` fazem parte do seu valor e ficam
+    /// intactas.
     #[allow(dead_code)]
     pub fn format_source(&self, source: &str) -> String {
-        let lines: Vec<&str> = source.lines().collect();
         let mut formatted = String::new();
         let mut indent_level: usize = 0;
+        let string_lines = Self::string_continuation_lines(source);
+
+        for (index, line) in source.lines().enumerate() {
+            if string_lines.contains(&index) {
+                formatted.push_str(line);
+                formatted.push('\n');
+                continue;
+            }
 
-        for line in lines {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 formatted.push('\n');
                 continue;
             }
 
-            // Ajustar indentação baseado em palavras-chave
-            if trimmed.starts_with('}') {
+            let (code, comment) = Self::split_comment(trimmed);
+            let tokens = if trimmed.contains("/*") || trimmed.contains("*/") {
+                None
+            } else {
+                Self::line_tokens(code)
+            };
+
+            let Some(tokens) = tokens else {
+                // Ajustar indentação baseado nas chaves do texto original
+                if trimmed.starts_with('}') {
+                    indent_level = indent_level.saturating_sub(1);
+                }
+                let indent = self.indent(indent_level);
+                formatted.push_str(&format!("{}{}\n", indent, trimmed));
+                if trimmed.ends_with('{') {
+                    indent_level += 1;
+                }
+                continue;
+            };
+
+            let leading_close = matches!(tokens.first(), Some((Token::RightBrace, _)));
+            if leading_close {
                 indent_level = indent_level.saturating_sub(1);
             }
 
-            // Aplicar indentação
-            let indent = " ".repeat(indent_level * self.indent_size);
-            formatted.push_str(&format!("{}{}\n", indent, trimmed));
+            let (mut text, breaks) = Self::join_tokens(&tokens);
+            if let Some(comment) = comment {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(comment);
+            }
 
-            // Aumentar indentação para blocos
-            if trimmed.ends_with('{') {
-                indent_level += 1;
+            // Aplicar indentação, quebrando linhas longas
+            for (i, piece) in self.wrap(&text, &breaks, indent_level).into_iter().enumerate() {
+                let level = if i == 0 { indent_level } else { indent_level + 1 };
+                formatted.push_str(&format!("{}{}\n", self.indent(level), piece));
             }
+
+            // Atualizar indentação pelas chaves abertas e fechadas na linha
+            let opens = tokens.iter().filter(|(t, _)| *t == Token::LeftBrace).count();
+            let closes = tokens.iter().filter(|(t, _)| *t == Token::RightBrace).count()
+                - leading_close as usize;
+            indent_level = (indent_level + opens).saturating_sub(closes);
         }
 
         formatted
     }
+
+    /// Índices (a partir de 0) das linhas após a abertura de uma string
+    /// `"""` até a linha que a fecha. Comentários e strings comuns são
+    /// pulados para que aspas dentro deles não contem
+    fn string_continuation_lines(source: &str) -> HashSet<usize> {
+        let bytes = source.as_bytes();
+        let mut lines = HashSet::new();
+        let mut line = 0;
+        let mut i = 0;
+
+        let mut skip_to = |i: &mut usize, end: usize, line: &mut usize, continuation: bool| {
+            let newlines = source[*i..end].matches('\n').count();
+            if continuation {
+                lines.extend(*line + 1..=*line + newlines);
+            }
+            *line += newlines;
+            *i = end;
+        };
+
+        while i < bytes.len() {
+            let rest = &source[i..];
+            if let Some(contents) = rest.strip_prefix("\"\"\"") {
+                let end = contents.find("\"\"\"").map_or(source.len(), |end| i + end + 6);
+                skip_to(&mut i, end, &mut line, true);
+            } else if rest.starts_with("/*") {
+                let end = rest.find("*/").map_or(source.len(), |end| i + end + 2);
+                skip_to(&mut i, end, &mut line, false);
+            } else if rest.starts_with("//") {
+                i += rest.find('\n').unwrap_or(rest.len());
+            } else if matches!(bytes[i], b'"' | b'\'') {
+                // String ou caractere comum: termina na aspa ou no fim da linha
+                let quote = bytes[i];
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote && bytes[i] != b'\n' {
+                    i += if bytes[i] == b'\\' && bytes.get(i + 1) != Some(&b'\n') { 2 } else { 1 };
+                }
+                if bytes.get(i) == Some(&quote) {
+                    i += 1;
+                }
+            } else {
+                if bytes[i] == b'\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+        }
+
+        lines
+    }
+
+    /// Separa um comentário `//` do código, ignorando `//` dentro de strings
+    fn split_comment(line: &str) -> (&str, Option<&str>) {
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (i, c) in line.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                '/' if !in_string && line[i..].starts_with("//") => {
+                    return (line[..i].trim_end(), Some(&line[i..]));
+                }
+                _ => {}
+            }
+        }
+
+        (line, None)
+    }
+
+    /// Tokeniza uma linha, retornando cada token com o texto original
+    fn line_tokens(code: &str) -> Option<Vec<(Token, &str)>> {
+        let tokens = Lexer::new(code).tokenize().ok()?;
+
+        Some(
+            tokens
+                .into_iter()
+                .filter(|info| info.token != Token::Eof)
+                .map(|info| {
+                    // A linha é única, então a coluna é o deslocamento em bytes
+                    let start = info.location.column - 1;
+                    (info.token, &code[start..start + info.location.length])
+                })
+                .collect(),
+        )
+    }
+
+    fn indent(&self, level: usize) -> String {
+        if self.options.use_tabs {
+            "\t".repeat(level)
+        } else {
+            " ".repeat(level * self.options.indent_width)
+        }
+    }
+
+    /// Divide uma linha nos pontos de quebra permitidos para respeitar
+    /// `max_line_length`; as continuações ganham um nível de indentação
+    fn wrap<'a>(&self, text: &'a str, breaks: &[usize], indent_level: usize) -> Vec<&'a str> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        let mut width = indent_level * self.options.indent_width;
+
+        while width + text.len() - start > self.options.max_line_length {
+            let mut candidates = breaks.iter().copied().filter(|&b| b > start);
+            let fitting = candidates
+                .clone()
+                .rfind(|&b| width + b - start <= self.options.max_line_length);
+            let Some(split) = fitting.or_else(|| candidates.next()) else {
+                break;
+            };
+
+            pieces.push(&text[start..split]);
+            start = split + 1; // Pular o espaço após o ponto de quebra
+            width = (indent_level + 1) * self.options.indent_width;
+        }
+
+        pieces.push(&text[start..]);
+        pieces
+    }
+
+    /// Junta os tokens com o espaçamento normalizado, retornando também as
+    /// posições logo após operadores binários e vírgulas onde a linha pode
+    /// ser quebrada
+    fn join_tokens(tokens: &[(Token, &str)]) -> (String, Vec<usize>) {
+        let mut text = String::new();
+        let mut breaks = Vec::new();
+        let mut previous: Option<&Token> = None;
+        let mut previous_is_unary = false;
+
+        for (token, lexeme) in tokens {
+            if let Some(prev) = previous {
+                if Self::needs_space(prev, previous_is_unary, token) {
+                    if !previous_is_unary && Self::is_break_point(prev) {
+                        breaks.push(text.len());
+                    }
+                    text.push(' ');
+                }
+            }
+
+            // `-` e `!` são unários quando não seguem um operando
+            previous_is_unary = match token {
+                Token::Not => true,
+                Token::Minus => !previous.is_some_and(Self::ends_operand),
+                _ => false,
+            };
+            text.push_str(lexeme);
+            previous = Some(token);
+        }
+
+        (text, breaks)
+    }
+
+    fn is_break_point(token: &Token) -> bool {
+        matches!(
+            token,
+            Token::Comma
+                | Token::Plus
+                | Token::Minus
+                | Token::Star
+                | Token::Slash
+                | Token::Percent
+                | Token::Equal
+                | Token::NotEqual
+                | Token::LessThan
+                | Token::LessThanEqual
+                | Token::GreaterThan
+                | Token::GreaterThanEqual
+                | Token::And
+                | Token::Or
+                | Token::Assign
+        )
+    }
+
+    fn needs_space(previous: &Token, previous_is_unary: bool, current: &Token) -> bool {
+        match (previous, current) {
+            (_, Token::RightParen | Token::RightBracket | Token::Comma | Token::Semicolon)
+            | (_, Token::Colon | Token::Dot) => false,
+            (_, Token::PlusPlus | Token::MinusMinus) => false,
+            (Token::LeftParen | Token::LeftBracket | Token::Dot, _) => false,
+            _ if previous_is_unary => false,
+            (Token::Identifier(_) | Token::RightParen, Token::LeftParen) => false,
+            (Token::Identifier(_), Token::LeftBracket) => false,
+            _ => true,
+        }
+    }
+
+    fn ends_operand(token: &Token) -> bool {
+        matches!(
+            token,
+            Token::Identifier(_)
+                | Token::Integer(_)
+                | Token::Float(_)
+                | Token::String(_)
+                | Token::Boolean(_)
+                | Token::Char(_)
+                | Token::RightParen
+                | Token::RightBracket
+        )
+    }
 }
 
 /// Utilitário para análise de complexidade ciclomática
@@ -123,6 +589,12 @@ pub struct ComplexityAnalyzer {
     complexity_map: HashMap<String, usize>,
 }
 
+impl Default for ComplexityAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ComplexityAnalyzer {
     #[allow(dead_code)]
     pub fn new() -> Self {
@@ -138,11 +610,42 @@ impl ComplexityAnalyzer {
         complexity
     }
 
+    /// Complexidade ciclomática de McCabe: uma função começa com 1 e cada
+    /// ponto de decisão soma 1 — cada `if`, cada `while` e cada `&&`/`||`.
+    /// Ramos `then`/`else` e corpos de laços são percorridos recursivamente,
+    /// então estruturas aninhadas contam integralmente; o `else` em si não
+    /// é um ponto de decisão.
     #[allow(dead_code)]
     fn calculate_complexity(&self, statement: &crate::ast::Statement) -> usize {
         match statement {
-            crate::ast::Statement::If(_) => 1,
-            crate::ast::Statement::While(_) => 1,
+            crate::ast::Statement::If(if_stmt) => {
+                let mut complexity = 1 + self.count_logical_operators(&if_stmt.condition);
+                complexity += self.calculate_complexity(&if_stmt.then_branch);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    complexity += self.calculate_complexity(else_branch);
+                }
+                complexity
+            }
+            crate::ast::Statement::While(while_stmt) => {
+                1 + self.count_logical_operators(&while_stmt.condition)
+                    + self.calculate_complexity(&while_stmt.body)
+            }
+            crate::ast::Statement::Expression(expr_stmt) => {
+                self.count_logical_operators(&expr_stmt.expression)
+            }
+            crate::ast::Statement::Declaration(decl) => {
+                decl.initializer.as_ref().map_or(0, |init| self.count_logical_operators(init))
+            }
+            crate::ast::Statement::Assignment(assign) => {
+                self.count_logical_operators(&assign.value)
+            }
+            crate::ast::Statement::Return(return_stmt) => {
+                return_stmt.value.as_ref().map_or(0, |value| self.count_logical_operators(value))
+            }
+            crate::ast::Statement::Break(_)
+            | crate::ast::Statement::Continue(_)
+            | crate::ast::Statement::Empty(_)
+            | crate::ast::Statement::Error(_) => 0,
             crate::ast::Statement::Function(func) => {
                 let mut complexity = 1; // Base complexity
                 for stmt in &func.body.statements {
@@ -157,7 +660,36 @@ impl ComplexityAnalyzer {
                 }
                 complexity
             }
-            _ => 0,
+        }
+    }
+
+    /// Cada `&&` ou `||` é um ponto de decisão adicional (definição de McCabe)
+    fn count_logical_operators(&self, expression: &crate::ast::Expression) -> usize {
+        use crate::ast::{BinaryOperator, Expression};
+
+        match expression {
+            Expression::Binary(binary) => {
+                let own = matches!(binary.operator, BinaryOperator::And | BinaryOperator::Or) as usize;
+                own + self.count_logical_operators(&binary.left)
+                    + self.count_logical_operators(&binary.right)
+            }
+            Expression::Unary(unary) => self.count_logical_operators(&unary.operand),
+            Expression::Call(call) => call
+                .arguments
+                .iter()
+                .map(|arg| self.count_logical_operators(arg))
+                .sum(),
+            Expression::IndirectCall(call) => {
+                self.count_logical_operators(&call.callee)
+                    + call
+                        .arguments
+                        .iter()
+                        .map(|arg| self.count_logical_operators(arg))
+                        .sum::<usize>()
+            }
+            Expression::Assignment(assign) => self.count_logical_operators(&assign.value),
+            Expression::Cast(cast) => self.count_logical_operators(&cast.expression),
+            Expression::Literal(_) | Expression::Identifier(_) | Expression::Error(_) => 0,
         }
     }
 
@@ -183,63 +715,498 @@ impl ComplexityAnalyzer {
     }
 }
 
-/// Utilitário para otimizações básicas
-#[allow(dead_code)]
+/// Resultado de uma passagem de otimização
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassReport {
+    /// Nome da passagem que gerou o relatório
+    pub pass: String,
+    /// Número de alterações feitas na AST
+    pub changes: usize,
+}
+
+/// Passagem de otimização sobre a AST. `Optimizer` executa as passagens em
+/// ordem; implemente este trait para adicionar passagens próprias.
+pub trait Pass {
+    /// Nome da passagem, usado nos relatórios
+    fn name(&self) -> &str;
+
+    fn run(&self, program: &mut crate::ast::Program) -> Result<PassReport, String>;
+}
+
+/// Dobramento de constantes, como `2 + 3` -> `5`
+pub struct ConstantFolding;
+
+impl Pass for ConstantFolding {
+    fn name(&self) -> &str {
+        "constant-folding"
+    }
+
+    fn run(&self, _program: &mut crate::ast::Program) -> Result<PassReport, String> {
+        // Implementar dobramento de constantes
+        Ok(PassReport { pass: self.name().to_string(), changes: 0 })
+    }
+}
+
+/// Eliminação de código morto, como variáveis não utilizadas
+pub struct DeadCodeElimination;
+
+impl Pass for DeadCodeElimination {
+    fn name(&self) -> &str {
+        "dead-code-elimination"
+    }
+
+    fn run(&self, _program: &mut crate::ast::Program) -> Result<PassReport, String> {
+        // Implementar eliminação de código morto
+        Ok(PassReport { pass: self.name().to_string(), changes: 0 })
+    }
+}
+
+/// Simplificação de operações com elemento neutro, como `x + 0` -> `x`
+pub struct ExpressionSimplification;
+
+impl Pass for ExpressionSimplification {
+    fn name(&self) -> &str {
+        "expression-simplification"
+    }
+
+    fn run(&self, program: &mut crate::ast::Program) -> Result<PassReport, String> {
+        let mut simplifier = ExpressionSimplifier { changes: 0 };
+        simplifier.visit_program_mut(program);
+        Ok(PassReport { pass: self.name().to_string(), changes: simplifier.changes })
+    }
+}
+
+/// Executa uma sequência ordenada de passagens de otimização sobre a AST
 pub struct Optimizer {
-    config: CompilerConfig,
+    passes: Vec<Box<dyn Pass>>,
 }
 
 impl Optimizer {
-    #[allow(dead_code)]
+    /// Pipeline padrão para o nível de otimização da configuração
     pub fn new(config: CompilerConfig) -> Self {
-        Self { config }
+        Self::for_level(config._optimization_level)
     }
 
-    #[allow(dead_code)]
-    pub fn optimize_ast(&self, program: &mut crate::ast::Program) -> Result<(), String> {
-        match self.config._optimization_level {
-            0 => Ok(()), // Sem otimizações
-            1 => self.constant_folding(program),
-            2 => {
-                self.constant_folding(program)?;
-                self.dead_code_elimination(program)
+    /// Pipeline padrão de cada nível: 1 dobra constantes, 2 também elimina
+    /// código morto e 3 também simplifica expressões. Níveis acima de
+    /// `MAX_OPTIMIZATION_LEVEL` são rejeitados por `CompilerConfig::validate`.
+    pub fn for_level(level: u8) -> Self {
+        let mut optimizer = Self::empty();
+        if level >= 1 {
+            optimizer = optimizer.with_pass(ConstantFolding);
+        }
+        if level >= 2 {
+            optimizer = optimizer.with_pass(DeadCodeElimination);
+        }
+        if level >= 3 {
+            optimizer = optimizer.with_pass(ExpressionSimplification);
+        }
+        optimizer
+    }
+
+    /// Otimizador sem passagens
+    pub fn empty() -> Self {
+        Self { passes: Vec::new() }
+    }
+
+    /// Adiciona uma passagem ao fim do pipeline
+    pub fn with_pass(mut self, pass: impl Pass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Passagens em ordem de execução, para reordenar, inserir ou remover
+    pub fn passes_mut(&mut self) -> &mut Vec<Box<dyn Pass>> {
+        &mut self.passes
+    }
+
+    /// Nomes das passagens em ordem de execução
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Executa as passagens em ordem, parando na primeira que falhar
+    pub fn optimize_ast(&self, program: &mut crate::ast::Program) -> Result<Vec<PassReport>, String> {
+        self.passes.iter().map(|pass| pass.run(program)).collect()
+    }
+}
+
+/// Remove operações com elemento neutro inteiro: `x + 0`, `0 + x`, `x - 0`,
+/// `x * 1`, `1 * x` e `x / 1` viram apenas `x`
+struct ExpressionSimplifier {
+    changes: usize,
+}
+
+impl ExpressionSimplifier {
+    fn is_integer(expression: &crate::ast::Expression, value: i64) -> bool {
+        matches!(
+            expression,
+            crate::ast::Expression::Literal(crate::ast::LiteralExpression {
+                value: crate::ast::Literal::Integer(n),
+                ..
+            }) if *n == value
+        )
+    }
+}
+
+impl VisitorMut for ExpressionSimplifier {
+    fn visit_expression_mut(&mut self, expression: &mut crate::ast::Expression) {
+        // Simplificar os filhos primeiro para que `(x + 0) * 1` também reduza
+        walk_expression_mut(self, expression);
+
+        let crate::ast::Expression::Binary(binary) = expression else {
+            return;
+        };
+        use crate::ast::BinaryOperator::*;
+        let location = binary.location.clone();
+        let replacement = match binary.operator {
+            Add if Self::is_integer(&binary.right, 0) => &mut binary.left,
+            Add if Self::is_integer(&binary.left, 0) => &mut binary.right,
+            Subtract if Self::is_integer(&binary.right, 0) => &mut binary.left,
+            Multiply | Divide if Self::is_integer(&binary.right, 1) => &mut binary.left,
+            Multiply if Self::is_integer(&binary.left, 1) => &mut binary.right,
+            _ => return,
+        };
+        let simplified = std::mem::replace(
+            replacement.as_mut(),
+            crate::ast::Expression::Identifier(crate::ast::IdentifierExpression {
+                name: String::new(),
+                location,
+            }),
+        );
+        *expression = simplified;
+        self.changes += 1;
+    }
+}
+
+/// Tipo de aresta entre blocos básicos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// Execução segue para o próximo bloco sem desvio condicional
+    Fallthrough,
+    /// Condição verdadeira
+    True,
+    /// Condição falsa
+    False,
+}
+
+/// Sequência de comandos sem desvios internos
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+    pub id: usize,
+    pub statements: Vec<String>,
+}
+
+/// Grafo de fluxo de controle de uma função. O bloco 0 é a entrada e o
+/// bloco 1 é a saída, alcançada por todo `return` e pelo fim da função.
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    pub function: String,
+    pub blocks: Vec<BasicBlock>,
+    pub edges: Vec<(usize, usize, EdgeKind)>,
+    /// Laços em construção: rótulo, cabeçalho (destino dos `continue`) e
+    /// bloco seguinte (destino dos `break`)
+    loops: Vec<(Option<String>, usize, usize)>,
+}
+
+impl ControlFlowGraph {
+    const ENTRY: usize = 0;
+    const EXIT: usize = 1;
+
+    /// Constrói o grafo a partir do corpo da função, dividindo os blocos
+    /// em cada `if`, `while` e `return`
+    pub fn build(function: &crate::ast::FunctionStatement) -> Self {
+        let mut cfg = Self {
+            function: function.name.clone(),
+            blocks: Vec::new(),
+            edges: Vec::new(),
+            loops: Vec::new(),
+        };
+        cfg.new_block();
+        cfg.new_block();
+
+        if let Some(last) = cfg.lower_statements(&function.body.statements, Self::ENTRY) {
+            cfg.edges.push((last, Self::EXIT, EdgeKind::Fallthrough));
+        }
+        cfg
+    }
+
+    fn new_block(&mut self) -> usize {
+        let id = self.blocks.len();
+        self.blocks.push(BasicBlock { id, statements: Vec::new() });
+        id
+    }
+
+    /// Laço alvo de um `break`/`continue`: o mais interno ou o do rótulo
+    fn loop_target(&self, label: &Option<String>) -> Option<&(Option<String>, usize, usize)> {
+        match label {
+            Some(_) => self.loops.iter().rev().find(|(name, _, _)| name == label),
+            None => self.loops.last(),
+        }
+    }
+
+    fn jump_text(keyword: &str, label: &Option<String>) -> String {
+        match label {
+            Some(label) => format!("{} {}", keyword, label),
+            None => keyword.to_string(),
+        }
+    }
+
+    /// Adiciona os comandos a partir do bloco `current` e retorna o bloco
+    /// onde a execução continua, ou `None` se todos os caminhos retornaram
+    fn lower_statements(&mut self, statements: &[crate::ast::Statement], current: usize) -> Option<usize> {
+        let mut current = Some(current);
+        for statement in statements {
+            // Comandos após um `return` formam um bloco sem predecessores
+            let block = match current {
+                Some(block) => block,
+                None => self.new_block(),
+            };
+            current = self.lower_statement(statement, block);
+        }
+        current
+    }
+
+    fn lower_statement(&mut self, statement: &crate::ast::Statement, current: usize) -> Option<usize> {
+        use crate::ast::Statement;
+
+        match statement {
+            Statement::If(if_stmt) => {
+                self.blocks[current].statements.push(format!("if ({})", describe_expression(&if_stmt.condition)));
+
+                let then_block = self.new_block();
+                self.edges.push((current, then_block, EdgeKind::True));
+                let then_end = self.lower_statement(&if_stmt.then_branch, then_block);
+
+                let else_end = match &if_stmt.else_branch {
+                    Some(else_branch) => {
+                        let else_block = self.new_block();
+                        self.edges.push((current, else_block, EdgeKind::False));
+                        self.lower_statement(else_branch, else_block)
+                    }
+                    None => None,
+                };
+
+                let join = self.new_block();
+                if if_stmt.else_branch.is_none() {
+                    self.edges.push((current, join, EdgeKind::False));
+                }
+                for end in [then_end, else_end].into_iter().flatten() {
+                    self.edges.push((end, join, EdgeKind::Fallthrough));
+                }
+                Some(join)
+            }
+            Statement::While(while_stmt) => {
+                let header = self.new_block();
+                self.edges.push((current, header, EdgeKind::Fallthrough));
+                self.blocks[header].statements.push(format!("while ({})", describe_expression(&while_stmt.condition)));
+
+                let body = self.new_block();
+                let after = self.new_block();
+                self.edges.push((header, body, EdgeKind::True));
+                self.loops.push((while_stmt.label.clone(), header, after));
+                if let Some(body_end) = self.lower_statement(&while_stmt.body, body) {
+                    self.edges.push((body_end, header, EdgeKind::Fallthrough));
+                }
+                self.loops.pop();
+
+                self.edges.push((header, after, EdgeKind::False));
+                Some(after)
+            }
+            Statement::Return(return_stmt) => {
+                let text = match &return_stmt.value {
+                    Some(value) => format!("return {}", describe_expression(value)),
+                    None => "return".to_string(),
+                };
+                self.blocks[current].statements.push(text);
+                self.edges.push((current, Self::EXIT, EdgeKind::Fallthrough));
+                None
+            }
+            Statement::Break(break_stmt) => {
+                self.blocks[current].statements.push(Self::jump_text("break", &break_stmt.label));
+                if let Some(&(_, _, after)) = self.loop_target(&break_stmt.label) {
+                    self.edges.push((current, after, EdgeKind::Fallthrough));
+                }
+                None
+            }
+            Statement::Continue(continue_stmt) => {
+                self.blocks[current].statements.push(Self::jump_text("continue", &continue_stmt.label));
+                if let Some(&(_, header, _)) = self.loop_target(&continue_stmt.label) {
+                    self.edges.push((current, header, EdgeKind::Fallthrough));
+                }
+                None
+            }
+            Statement::Block(block) => self.lower_statements(&block.statements, current),
+            Statement::Expression(expr_stmt) => {
+                self.blocks[current].statements.push(describe_expression(&expr_stmt.expression));
+                Some(current)
+            }
+            Statement::Declaration(decl) => {
+                let keyword = if decl.is_const { "const" } else { "var" };
+                let text = match (&decl.initializer, &decl.var_type) {
+                    (Some(init), crate::ast::Type::Inferred) => format!("{} {} = {}", keyword, decl.name, describe_expression(init)),
+                    (Some(init), var_type) => format!("{} {}: {} = {}", keyword, decl.name, var_type, describe_expression(init)),
+                    (None, var_type) => format!("{} {}: {}", keyword, decl.name, var_type),
+                };
+                self.blocks[current].statements.push(text);
+                Some(current)
             }
-            3 => {
-                self.constant_folding(program)?;
-                self.dead_code_elimination(program)?;
-                self.expression_simplification(program)
+            Statement::Assignment(assign) => {
+                self.blocks[current].statements.push(format!("{} = {}", assign.target, describe_expression(&assign.value)));
+                Some(current)
             }
-            _ => Err("Nível de otimização inválido".to_string()),
+            // Funções aninhadas são rejeitadas pela análise semântica
+            Statement::Function(_) | Statement::Empty(_) | Statement::Error(_) => Some(current),
         }
     }
 
-    #[allow(dead_code)]
-    fn constant_folding(&self, _program: &mut crate::ast::Program) -> Result<(), String> {
-        // Implementar dobramento de constantes
-        // Ex: 2 + 3 -> 5
-        Ok(())
+    /// Gera o grafo no formato DOT do Graphviz, como um subgrafo `cluster_<função>`
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str(&format!("    subgraph cluster_{} {{\n", self.function));
+        dot.push_str(&format!("        label=\"{}\";\n", self.function));
+
+        for block in &self.blocks {
+            let mut lines = Vec::new();
+            match block.id {
+                Self::ENTRY => lines.push("entrada".to_string()),
+                Self::EXIT => lines.push("saída".to_string()),
+                _ => {}
+            }
+            lines.extend(block.statements.iter().cloned());
+            if lines.is_empty() {
+                lines.push(format!("bloco {}", block.id));
+            }
+            let label = lines.iter().map(|line| dot_escape(line)).collect::<Vec<_>>().join("\\n");
+            dot.push_str(&format!("        {}_{} [label=\"{}\"];\n", self.function, block.id, label));
+        }
+
+        for (from, to, kind) in &self.edges {
+            let attributes = match kind {
+                EdgeKind::Fallthrough => String::new(),
+                EdgeKind::True => " [label=\"true\"]".to_string(),
+                EdgeKind::False => " [label=\"false\"]".to_string(),
+            };
+            dot.push_str(&format!("        {f}_{} -> {f}_{}{};\n", from, to, attributes, f = self.function));
+        }
+
+        dot.push_str("    }\n");
+        dot
     }
+}
 
-    #[allow(dead_code)]
-    fn dead_code_elimination(&self, _program: &mut crate::ast::Program) -> Result<(), String> {
-        // Implementar eliminação de código morto
-        // Ex: remover variáveis não utilizadas
-        Ok(())
+/// Gera o DOT com o grafo de fluxo de controle de todas as funções do programa
+pub fn cfg_to_dot(program: &crate::ast::Program) -> String {
+    let mut dot = String::from("digraph cfg {\n    node [shape=box];\n");
+    for statement in &program.statements {
+        if let crate::ast::Statement::Function(func) = statement {
+            dot.push_str(&ControlFlowGraph::build(func).to_dot());
+        }
     }
+    dot.push_str("}\n");
+    dot
+}
 
-    #[allow(dead_code)]
-    fn expression_simplification(&self, _program: &mut crate::ast::Program) -> Result<(), String> {
-        // Implementar simplificação de expressões
-        // Ex: x + 0 -> x, x * 1 -> x
-        Ok(())
+/// Texto curto de uma expressão, usado nos rótulos do grafo e em sugestões
+/// dos avisos. Subexpressões que se ligam mais fracamente que o operador em
+/// volta ganham parênteses, para o texto ter a mesma estrutura da árvore
+pub(crate) fn describe_expression(expression: &crate::ast::Expression) -> String {
+    use crate::ast::{Expression, UnaryOperator};
+
+    let list = |arguments: &[Expression]| {
+        arguments.iter().map(describe_expression).collect::<Vec<_>>().join(", ")
+    };
+    // Texto de `operand`, entre parênteses se ligar com força menor que `min`
+    let operand = |operand: &Expression, min: u8| {
+        let text = describe_expression(operand);
+        if expression_precedence(operand) < min {
+            format!("({})", text)
+        } else {
+            text
+        }
+    };
+
+    match expression {
+        Expression::Literal(lit) => lit.value.to_string(),
+        Expression::Identifier(ident) => ident.name.clone(),
+        Expression::Binary(binary) => {
+            // Os operadores binários associam à esquerda: à direita, mesma
+            // precedência também exige parênteses
+            let precedence = expression_precedence(expression);
+            format!(
+                "{} {} {}",
+                operand(&binary.left, precedence),
+                binary.operator,
+                operand(&binary.right, precedence + 1)
+            )
+        }
+        Expression::Unary(unary) => {
+            let operator = match unary.operator {
+                UnaryOperator::Not => "!",
+                UnaryOperator::Negate => "~",
+                UnaryOperator::Minus => "-",
+            };
+            format!("{}{}", operator, operand(&unary.operand, expression_precedence(expression)))
+        }
+        Expression::Call(call) => format!("{}({})", call.function, list(&call.arguments)),
+        Expression::IndirectCall(call) => {
+            format!("({})({})", describe_expression(&call.callee), list(&call.arguments))
+        }
+        Expression::Assignment(assign) => {
+            format!("{} = {}", assign.target, describe_expression(&assign.value))
+        }
+        Expression::Cast(cast) => format!(
+            "{} as {}",
+            operand(&cast.expression, expression_precedence(expression)),
+            cast.target_type
+        ),
+        Expression::Error(_) => "<erro>".to_string(),
+    }
+}
+
+/// Força com que uma expressão se liga, como no parser: atribuição, os
+/// níveis dos operadores binários (1 a 6), unários, `as` e, por fim, os
+/// termos primários
+fn expression_precedence(expression: &crate::ast::Expression) -> u8 {
+    use crate::ast::{BinaryOperator, Expression};
+
+    match expression {
+        Expression::Assignment(_) => 0,
+        Expression::Binary(binary) => match binary.operator {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
+            BinaryOperator::LessThan
+            | BinaryOperator::LessThanEqual
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanEqual => 4,
+            BinaryOperator::Add | BinaryOperator::Subtract => 5,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 6,
+        },
+        Expression::Unary(_) => 7,
+        Expression::Cast(_) => 8,
+        _ => 9,
     }
 }
 
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 /// Utilitário para validação de código
 #[allow(dead_code)]
 pub struct CodeValidator {
     warnings: Vec<String>,
     errors: Vec<String>,
+    /// Variáveis não utilizadas da última validação, com a posição da
+    /// declaração
+    unused_variables: Vec<(String, crate::ast::Location)>,
+}
+
+impl Default for CodeValidator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CodeValidator {
@@ -248,6 +1215,7 @@ impl CodeValidator {
         Self {
             warnings: Vec::new(),
             errors: Vec::new(),
+            unused_variables: Vec::new(),
         }
     }
 
@@ -278,9 +1246,119 @@ impl CodeValidator {
         self.errors.is_empty()
     }
 
+    /// Avisa sobre variáveis locais declaradas que nunca são lidas
     #[allow(dead_code)]
-    fn check_unused_variables(&mut self, _program: &crate::ast::Program) {
-        // Implementar verificação de variáveis não utilizadas
+    fn check_unused_variables(&mut self, program: &crate::ast::Program) {
+        self.unused_variables = Self::unused_variables(program);
+        for (name, location) in &self.unused_variables {
+            self.warnings.push(format!(
+                "Variável '{}' declarada mas não utilizada na linha {}, coluna {}",
+                name, location.line, location.column
+            ));
+        }
+    }
+
+    /// Variáveis não utilizadas encontradas por `validate`, com a posição
+    /// da declaração
+    pub fn get_unused_variables(&self) -> &[(String, crate::ast::Location)] {
+        &self.unused_variables
+    }
+
+    /// Variáveis locais declaradas que nunca são lidas, com a posição da
+    /// declaração; apenas atribuir um valor não conta como uso. Nomes que
+    /// começam com `_` são ignorados, como em Rust, para marcar variáveis
+    /// propositalmente não lidas
+    pub fn unused_variables(program: &crate::ast::Program) -> Vec<(String, crate::ast::Location)> {
+        let mut unused = Vec::new();
+        for statement in &program.statements {
+            if let crate::ast::Statement::Function(func) = statement {
+                let mut declared = Vec::new();
+                let mut used = HashSet::new();
+                for stmt in &func.body.statements {
+                    Self::collect_names(stmt, &mut declared, &mut used);
+                }
+
+                unused.extend(
+                    declared
+                        .into_iter()
+                        .filter(|(name, _)| !name.starts_with('_') && !used.contains(name)),
+                );
+            }
+        }
+        unused
+    }
+
+    /// Coleta as variáveis declaradas e os nomes lidos em uma declaração
+    fn collect_names(
+        statement: &crate::ast::Statement,
+        declared: &mut Vec<(String, crate::ast::Location)>,
+        used: &mut HashSet<String>,
+    ) {
+        use crate::ast::Statement;
+
+        match statement {
+            Statement::Expression(expr_stmt) => Self::collect_used(&expr_stmt.expression, used),
+            Statement::Declaration(decl) => {
+                declared.push((decl.name.clone(), decl.location.clone()));
+                if let Some(initializer) = &decl.initializer {
+                    Self::collect_used(initializer, used);
+                }
+            }
+            Statement::Assignment(assign) => Self::collect_used(&assign.value, used),
+            Statement::If(if_stmt) => {
+                Self::collect_used(&if_stmt.condition, used);
+                Self::collect_names(&if_stmt.then_branch, declared, used);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    Self::collect_names(else_branch, declared, used);
+                }
+            }
+            Statement::While(while_stmt) => {
+                Self::collect_used(&while_stmt.condition, used);
+                Self::collect_names(&while_stmt.body, declared, used);
+            }
+            Statement::Return(return_stmt) => {
+                if let Some(value) = &return_stmt.value {
+                    Self::collect_used(value, used);
+                }
+            }
+            Statement::Break(_) | Statement::Continue(_) | Statement::Empty(_) | Statement::Error(_) => {}
+            Statement::Block(block) => {
+                for stmt in &block.statements {
+                    Self::collect_names(stmt, declared, used);
+                }
+            }
+            Statement::Function(_) => {}
+        }
+    }
+
+    fn collect_used(expression: &crate::ast::Expression, used: &mut HashSet<String>) {
+        use crate::ast::Expression;
+
+        match expression {
+            Expression::Identifier(identifier) => {
+                used.insert(identifier.name.clone());
+            }
+            Expression::Binary(binary) => {
+                Self::collect_used(&binary.left, used);
+                Self::collect_used(&binary.right, used);
+            }
+            Expression::Unary(unary) => Self::collect_used(&unary.operand, used),
+            Expression::Call(call) => {
+                used.insert(call.function.clone());
+                for argument in &call.arguments {
+                    Self::collect_used(argument, used);
+                }
+            }
+            Expression::IndirectCall(call) => {
+                Self::collect_used(&call.callee, used);
+                for argument in &call.arguments {
+                    Self::collect_used(argument, used);
+                }
+            }
+            Expression::Assignment(assign) => Self::collect_used(&assign.value, used),
+            Expression::Cast(cast) => Self::collect_used(&cast.expression, used),
+            Expression::Literal(_) | Expression::Error(_) => {}
+        }
     }
 
     #[allow(dead_code)]
@@ -356,6 +1434,9 @@ impl DocumentationGenerator {
         for statement in &program.statements {
             if let crate::ast::Statement::Function(func) = statement {
                 docs.push_str(&format!("### {}\n\n", func.name));
+                if let Some(doc) = &func.doc {
+                    docs.push_str(&format!("{}\n\n", doc));
+                }
                 docs.push_str(&format!("**Tipo de retorno:** {}\n\n", func.return_type));
                 
                 if !func.parameters.is_empty() {
@@ -363,7 +1444,7 @@ impl DocumentationGenerator {
                     for param in &func.parameters {
                         docs.push_str(&format!("- `{}`: {}\n", param.name, param.param_type));
                     }
-                    docs.push_str("\n");
+                    docs.push('\n');
                 }
             }
         }
@@ -372,27 +1453,88 @@ impl DocumentationGenerator {
     }
 
     #[allow(dead_code)]
-    fn generate_html(&self, _program: &crate::ast::Program) -> String {
-        let mut docs = String::from("<html><head><title>Documentação</title></head><body>");
-        docs.push_str("<h1>Documentação do Código</h1>");
-        
-        // Implementar geração HTML
-        
-        docs.push_str("</body></html>");
+    fn generate_html(&self, program: &crate::ast::Program) -> String {
+        let mut docs = String::from("<html><head><title>Documentação</title></head><body>\n");
+        docs.push_str("<h1>Documentação do Código</h1>\n");
+
+        // Documentar funções
+        docs.push_str("<h2>Funções</h2>\n");
+        for statement in &program.statements {
+            if let crate::ast::Statement::Function(func) = statement {
+                docs.push_str(&format!("<h2>{}</h2>\n", html_escape(&func.name)));
+                if let Some(doc) = &func.doc {
+                    docs.push_str(&format!("<p>{}</p>\n", html_escape(doc).replace('\n', "<br>")));
+                }
+                docs.push_str(&format!(
+                    "<p><strong>Tipo de retorno:</strong> <code>{}</code></p>\n",
+                    html_escape(&func.return_type.to_string())
+                ));
+
+                if !func.parameters.is_empty() {
+                    docs.push_str("<table>\n<tr><th>Parâmetro</th><th>Tipo</th></tr>\n");
+                    for param in &func.parameters {
+                        docs.push_str(&format!(
+                            "<tr><td>{}</td><td>{}</td></tr>\n",
+                            html_escape(&param.name),
+                            html_escape(&param.param_type.to_string())
+                        ));
+                    }
+                    docs.push_str("</table>\n");
+                }
+            }
+        }
+
+        docs.push_str("</body></html>\n");
         docs
     }
 
     #[allow(dead_code)]
-    fn generate_plain_text(&self, _program: &crate::ast::Program) -> String {
+    fn generate_plain_text(&self, program: &crate::ast::Program) -> String {
         let mut docs = String::from("DOCUMENTAÇÃO DO CÓDIGO\n");
         docs.push_str("=======================\n\n");
-        
-        // Implementar geração de texto simples
-        
+
+        // Documentar funções
+        docs.push_str("FUNÇÕES\n\n");
+        for statement in &program.statements {
+            if let crate::ast::Statement::Function(func) = statement {
+                docs.push_str(&format!("{}\n", func.name));
+                if let Some(doc) = &func.doc {
+                    for line in doc.lines() {
+                        docs.push_str(&format!("    {}\n", line));
+                    }
+                }
+                docs.push_str(&format!("    Tipo de retorno: {}\n", func.return_type));
+
+                if !func.parameters.is_empty() {
+                    docs.push_str("    Parâmetros:\n");
+                    for param in &func.parameters {
+                        docs.push_str(&format!("        {}: {}\n", param.name, param.param_type));
+                    }
+                }
+                docs.push('\n');
+            }
+        }
+
         docs
     }
 }
 
+/// Escapa os caracteres especiais de HTML
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl fmt::Display for CompilerStats {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use ruscompile::*;
 
 #[test]
@@ -182,7 +184,10 @@ fn test_logical_operations() {
             var a: bool = true;
             var b: bool = false;
             var result: bool = a && b || !a;
-            return result;
+            if (result) {
+                return 1;
+            }
+            return 0;
         }
     "#;
 
@@ -247,6 +252,21 @@ fn test_error_handling() {
     }
 }
 
+#[test]
+fn test_missing_semicolon_message_uses_source_spelling() {
+    let source = "var x: int = 1\nvar y: int = 2;";
+    
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let error = parser.parse().expect_err("Deveria falhar na análise sintática");
+
+    let message = error.to_string();
+    assert!(message.contains("';'"), "{}", message);
+    assert!(!message.contains("Semicolon"), "{}", message);
+}
+
 #[test]
 fn test_type_checking() {
     let source = r#"
@@ -357,4 +377,2715 @@ fn test_complex_expression() {
     assert!(assembly.contains("imul"));
     assert!(assembly.contains("sub"));
     assert!(assembly.contains("idiv"));
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_global_variable_in_data_section() {
+    let source = r#"
+        var g: int = 42;
+
+        func main() -> int {
+            return g;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+    
+    // A global deve ser emitida na seção de dados e acessada pelo rótulo
+    let data_section = assembly.split("section .text").next().unwrap();
+    assert!(data_section.contains("g: dq 42"));
+    assert!(assembly.contains("mov rax, [g]"));
+    assert!(!assembly.contains("[rbp-8]"));
+}
+
+#[test]
+fn test_global_variable_assignment_from_function() {
+    let source = r#"
+        var counter: int;
+
+        func main() -> int {
+            counter = counter + 1;
+            return counter;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+    
+    // Sem inicializador, a global é reservada na seção bss
+    assert!(assembly.contains("section .bss"));
+    assert!(assembly.contains("counter: resq 1"));
+    assert!(assembly.contains("mov [counter], rax"));
+}
+
+#[test]
+fn test_parser_recovery_stops_at_max_errors() {
+    // 50 declarações quebradas: o parser deve parar após 20 erros
+    let source = "var = ;\n".repeat(50);
+
+    let mut lexer = Lexer::new(&source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let (_, errors) = parser.parse_with_recovery();
+
+    assert_eq!(errors.len(), 21);
+    assert!(errors[..20]
+        .iter()
+        .all(|e| e.to_string().contains("Esperado nome de variável")));
+    assert!(errors[20].to_string().contains("demasiados erros"));
+}
+
+#[test]
+fn test_parser_recovery_collects_errors_and_valid_statements() {
+    let source = r#"
+        var a: int = ;
+        var b: int = 2;
+        func main() -> int {
+            var c: int = * 3;
+            return b;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::with_config(tokens, ParserConfig { max_errors: 20, ..ParserConfig::default() });
+    let (program, errors) = parser.parse_with_recovery();
+
+    assert_eq!(errors.len(), 2);
+    // As declarações com erro continuam na árvore, com `Expression::Error`
+    // no lugar do inicializador
+    assert_eq!(program.statements.len(), 3);
+    assert!(matches!(
+        &program.statements[0],
+        Statement::Declaration(decl) if matches!(decl.initializer, Some(Expression::Error(_)))
+    ));
+    assert!(matches!(&program.statements[2], Statement::Function(f) if f.body.statements.len() == 2));
+}
+
+#[test]
+fn test_parser_recovery_reports_missing_expression_once() {
+    let source = "func main() -> int { var x: int = }; return 0; }";
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let (_, errors) = parser.parse_with_recovery();
+
+    // Um erro para o `}` no lugar do inicializador e um para o `}` que
+    // sobra no fim, sem o `Esperado ';'` no mesmo token de cada um
+    let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+    assert_eq!(errors.len(), 2, "{:?}", messages);
+    assert!(messages.iter().all(|error| !error.contains("Esperado ';'")), "{:?}", messages);
+}
+
+#[test]
+fn test_expression_types_annotated_after_analysis() {
+    let source = r#"
+        func main() -> int {
+            var x: float = 1 + 2.5;
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    let Statement::Function(func) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::Declaration(decl) = &func.body.statements[0] else {
+        panic!("Esperada uma declaração");
+    };
+    let initializer = decl.initializer.as_ref().unwrap();
+    let Expression::Binary(binary) = initializer else {
+        panic!("Esperada uma expressão binária");
+    };
+
+    let types = analyzer.expression_types();
+    assert_eq!(types.get(initializer), Some(&Type::Float));
+    assert_eq!(types.get(&binary.left), Some(&Type::Int));
+    assert_eq!(types.get(&binary.right), Some(&Type::Float));
+}
+
+#[test]
+fn test_shadowed_variable_in_inner_block() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            {
+                var x: int = 2;
+                x = x + 1;
+            }
+            return x;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    // O x interno ocupa outro slot e o externo volta a ser usado depois do bloco
+    assert!(assembly.contains("mov [rbp-8], rax"));
+    assert!(assembly.contains("mov [rbp-16], rax"));
+    let after_block = assembly.rsplit("mov [rbp-16], rax").next().unwrap();
+    assert!(after_block.contains("mov rax, [rbp-8]"));
+    assert!(!after_block.contains("[rbp-16]"));
+}
+
+#[test]
+fn test_redeclaration_in_same_scope_fails() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            var x: int = 2;
+            return x;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_err());
+}
+
+#[test]
+fn test_duplicate_parameter_names() {
+    let source = "func f(x: int, x: int) -> int {\n    return x;\n}\n";
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Parâmetro duplicado deveria falhar");
+
+    match error {
+        CompilerError::SemanticError { message, line, column } => {
+            assert_eq!(message, "parâmetro 'x' duplicado");
+            assert_eq!(line, Some(1));
+            assert_eq!(column, Some(16));
+        }
+        other => panic!("Erro inesperado: {:?}", other),
+    }
+}
+
+#[test]
+fn test_distinct_parameter_names() {
+    let source = r#"
+        func f(x: int, y: int) -> int {
+            return x + y;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_file_read_error_preserves_io_source() {
+    use std::error::Error;
+
+    let mut compiler = Compiler::new();
+    let error = compiler
+        .compile_file("/caminho/que/nao/existe.src")
+        .expect_err("Arquivo inexistente deveria falhar");
+
+    assert!(matches!(error, CompilerError::FileReadError(..)));
+    let source = error.source().expect("FileReadError deveria ter source()");
+    let io_error = source
+        .downcast_ref::<std::io::Error>()
+        .expect("source() deveria ser um io::Error");
+    assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_comparison_chaining_rejected() {
+    let source = r#"
+        func main() -> int {
+            var ok: bool = 1 < 2 < 3;
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Encadeamento deveria falhar");
+
+    assert!(error
+        .to_string()
+        .contains("encadeamento de comparações não é suportado; use &&"));
+}
+
+#[test]
+fn test_comparisons_joined_with_and() {
+    let source = r#"
+        func main() -> int {
+            var ok: bool = 1 < 2 && 2 < 3;
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_function_typed_variable() {
+    let source = r#"
+        func add(a: int, b: int) -> int {
+            return a + b;
+        }
+
+        func main() -> int {
+            var f: (int, int) -> int = add;
+            var result: int = f(1, 2);
+            return result;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    // A função é armazenada pelo endereço e chamada indiretamente
+    assert!(assembly.contains("mov rax, add"));
+    assert!(assembly.contains("call rax"));
+}
+
+#[test]
+fn test_function_typed_variable_checks_arguments() {
+    let mismatched_signature = r#"
+        func add(a: int, b: int) -> int {
+            return a + b;
+        }
+
+        func main() -> int {
+            var f: (int) -> int = add;
+            return 0;
+        }
+    "#;
+    let bad_argument = r#"
+        func add(a: int, b: int) -> int {
+            return a + b;
+        }
+
+        func main() -> int {
+            var f: (int, int) -> int = add;
+            return f(1, "dois");
+        }
+    "#;
+    // Sem conversão na chamada indireta, int não vale por float na assinatura
+    let coerced_signature = r#"
+        func g(x: int) -> int {
+            return x;
+        }
+
+        func main() -> int {
+            var f: (float) -> float = g;
+            f(1.5);
+            return 0;
+        }
+    "#;
+
+    for source in [mismatched_signature, bad_argument, coerced_signature] {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().expect("Falha na análise sintática");
+
+        let mut analyzer = SemanticAnalyzer::new();
+        let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+        assert!(matches!(error, CompilerError::TypeError { .. }));
+    }
+}
+
+#[test]
+fn test_indirect_call_of_returned_function() {
+    let source = r#"
+        func twice(x: int) -> int {
+            return x * 2;
+        }
+
+        func pick() -> (int) -> int {
+            return twice;
+        }
+
+        func main() -> int {
+            return pick()(21);
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    assert!(assembly.contains("mov rax, twice"));
+    assert!(assembly.contains("call pick"));
+    assert!(assembly.contains("call rax"));
+}
+
+#[test]
+fn test_int_return_coerced_in_float_function() {
+    let source = r#"
+        func f() -> float {
+            return 3;
+        }
+
+        func main() -> int {
+            var x: float = f();
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0).with_expression_types(analyzer.take_expression_types());
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    let function = &assembly[assembly.find("f:\n").unwrap()..assembly.find("main:\n").unwrap()];
+    assert!(function.contains("    push 3\n    pop rax\n    cvtsi2sd xmm0, rax\n"));
+    assert_eq!(assembly.matches("cvtsi2sd").count(), 1);
+}
+
+#[test]
+fn test_doc_comment_attached_to_function() {
+    let source = r#"
+        /// Soma dois inteiros.
+        /// Retorna o resultado.
+        func add(a: int, b: int) -> int {
+            return a + b;
+        }
+
+        // Comentário comum não é documentação
+        func main() -> int {
+            /// Ignorado: não precede uma função
+            return add(1, 2);
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let Statement::Function(add) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::Function(main) = &ast.statements[1] else {
+        panic!("Esperada uma função");
+    };
+    assert_eq!(add.doc.as_deref(), Some("Soma dois inteiros.\nRetorna o resultado."));
+    assert_eq!(main.doc, None);
+
+    let docs = DocumentationGenerator::new(DocumentationFormat::Markdown).generate_docs(&ast);
+    assert!(docs.contains("### add\n\nSoma dois inteiros.\nRetorna o resultado.\n"));
+}
+
+#[test]
+fn test_unreachable_code_after_return_warns() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            return x;
+            x = 2;
+            x = 3;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    let warnings = analyzer.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].location.line, 5);
+    assert!(warnings[0].message.contains("inalcançável"));
+}
+
+#[test]
+fn test_return_as_last_statement_has_no_warning() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            if (x > 0) {
+                return 1;
+            }
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    assert!(analyzer.warnings().is_empty());
+}
+
+#[test]
+fn test_increment_and_decrement_statements() {
+    let source = r#"
+        func main() -> int {
+            var i: int = 0;
+            var j: int = 10;
+            while (i < 10) {
+                i++;
+                j--;
+            }
+            return i + j;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    assert!(assembly.contains("    mov rax, [rbp-8]\n    push rax\n    push 1\n    pop rbx\n    pop rax\n"));
+    assert!(assembly.contains("    add rax, rbx\n    push rax\n    pop rax\n    mov [rbp-8], rax\n"));
+    assert!(assembly.contains("    sub rax, rbx\n    push rax\n    pop rax\n    mov [rbp-16], rax\n"));
+}
+
+#[test]
+fn test_increment_requires_variable() {
+    for source in ["func main() -> int { (1 + 2)++; return 0; }", "func main() -> int { 5++; return 0; }"] {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+        let mut parser = Parser::new(tokens);
+        let error = parser.parse().expect_err("Deveria falhar na análise sintática");
+        assert!(matches!(error, CompilerError::SyntaxError { .. }));
+    }
+
+    let source = "func main() -> int { var b: bool = true; b++; return 0; }";
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+    assert!(matches!(error, CompilerError::TypeError { .. }));
+}
+
+#[test]
+fn test_scope_introspection() {
+    use ruscompile::semantic::{Scope, Symbol};
+
+    fn variable(name: &str, symbol_type: Type) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            symbol_type,
+            is_function: false,
+            parameters: vec![],
+            return_type: None,
+            location: None,
+            value: None,
+        }
+    }
+
+    let mut outer = Scope::new();
+    outer.define(variable("x", Type::Int)).unwrap();
+    outer.define(variable("y", Type::Int)).unwrap();
+
+    let mut inner = Scope::with_parent(outer);
+    inner.define(variable("x", Type::Float)).unwrap();
+    inner.define(variable("z", Type::Bool)).unwrap();
+
+    let mut local: Vec<&str> = inner.names().collect();
+    local.sort();
+    assert_eq!(local, ["x", "z"]);
+
+    let visible = inner.all_visible();
+    let names: Vec<&str> = visible.iter().map(|symbol| symbol.name.as_str()).collect();
+    assert_eq!(names, ["x", "y", "z"]);
+    assert_eq!(visible[0].symbol_type, Type::Float);
+}
+
+#[test]
+fn test_nested_function_rejected() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            func helper() -> int {
+                return x;
+            }
+            return helper();
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+
+    match error {
+        CompilerError::SemanticError { message, line, .. } => {
+            assert!(message.contains("funções aninhadas"));
+            assert!(message.contains("'helper'"));
+            assert_eq!(line, Some(4));
+        }
+        other => panic!("Erro inesperado: {:?}", other),
+    }
+}
+
+#[test]
+fn test_cast_expressions() {
+    let source = r#"
+        func main() -> int {
+            var x: float = 3 as float;
+            var y: int = x as int;
+            var z: int = true as int;
+            return y + z;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    let Statement::Function(func) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::Declaration(decl) = &func.body.statements[0] else {
+        panic!("Esperada uma declaração");
+    };
+    let initializer = decl.initializer.as_ref().unwrap();
+    assert!(matches!(initializer, Expression::Cast(_)));
+    assert_eq!(analyzer.expression_types().get(initializer), Some(&Type::Float));
+    
+    let mut codegen = CodeGenerator::new(0).with_expression_types(analyzer.take_expression_types());
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    assert!(assembly.contains("    push 3\n    pop rax\n    cvtsi2sd xmm0, rax\n    movq rax, xmm0\n"));
+    assert!(assembly.contains("    cvttsd2si rax, xmm0\n"));
+    assert!(assembly.contains("    movzx rax, al\n"));
+}
+
+#[test]
+fn test_invalid_cast_rejected() {
+    let source = r#"
+        func main() -> int {
+            return "s" as int;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+    assert!(matches!(error, CompilerError::TypeError { .. }));
+    assert!(error.to_string().contains("string para int"));
+}
+
+#[test]
+fn test_parse_single_expression() {
+    let mut lexer = Lexer::new("1 + 2 * 3");
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let expression = parser.parse_expression().expect("Falha na análise sintática");
+
+    let Expression::Binary(sum) = &expression else {
+        panic!("Esperada uma expressão binária");
+    };
+    assert_eq!(sum.operator, BinaryOperator::Add);
+    assert!(matches!(&*sum.left, Expression::Literal(LiteralExpression { value: Literal::Integer(1), .. })));
+    let Expression::Binary(product) = &*sum.right else {
+        panic!("Esperada uma multiplicação à direita");
+    };
+    assert_eq!(product.operator, BinaryOperator::Multiply);
+
+    for source in ["1 + ", "1 2"] {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+        let mut parser = Parser::new(tokens);
+        let error = parser.parse_expression().expect_err("Deveria falhar na análise sintática");
+        assert!(matches!(error, CompilerError::SyntaxError { .. }));
+    }
+
+    // O token que sobra é citado como foi escrito
+    let mut parser = Parser::new(Lexer::new("1 2").tokenize().unwrap());
+    let error = parser.parse_expression().unwrap_err();
+    assert!(error.to_string().contains("Token inesperado após a expressão: '2'"), "{}", error);
+}
+
+#[test]
+fn test_assert_builtin() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 5;
+            assert(x > 0);
+            return x;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    assert!(assembly.contains("    setg al\n    movzx rax, al\n    push rax\n    pop rax\n    cmp rax, 0\n    je __assert_fail\n"));
+    assert!(assembly.contains("__assert_fail:\n    mov rax, 60\n    mov rdi, 1\n    syscall\n"));
+    assert!(!assembly.contains("call assert"));
+}
+
+#[test]
+fn test_assert_requires_bool() {
+    let source = r#"
+        func main() -> int {
+            assert(1);
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+    assert!(matches!(error, CompilerError::TypeError { .. }));
+}
+
+#[test]
+fn test_32_bit_integer_width() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 10;
+            var y: int = x - 3;
+            return y;
+        }
+    "#;
+
+    let config = CompilerConfig {
+        int_width: IntWidth::Bits32,
+        ..CompilerConfig::default()
+    };
+    let mut compiler = Compiler::with_config(config).expect("Configuração inválida");
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    assert!(assembly.contains("    mov dword [rbp-4], eax\n"));
+    assert!(assembly.contains("    mov eax, dword [rbp-4]\n"));
+    assert!(assembly.contains("    sub eax, ebx\n"));
+    assert!(assembly.contains("    mov dword [rbp-8], eax\n"));
+    assert!(!assembly.contains("sub rax, rbx"));
+    assert!(!assembly.contains("qword"));
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+    assert!(assembly.contains("    mov [rbp-16], rax\n"));
+    assert!(assembly.contains("    sub rax, rbx\n"));
+}
+
+#[test]
+fn test_duplicate_function_reports_both_locations() {
+    let source = r#"
+        func f() -> int {
+            return 1;
+        }
+
+        func f() -> int {
+            return 2;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+
+    match error {
+        CompilerError::SemanticError { message, line, .. } => {
+            assert_eq!(message, "função 'f' redefinida (definição anterior na linha 2)");
+            assert_eq!(line, Some(6));
+        }
+        other => panic!("Erro inesperado: {:?}", other),
+    }
+}
+
+#[test]
+fn test_multiline_string_in_data_section() {
+    let source = "func main() -> int {\n    print(\"\"\"linha 1\nlinha \"2\" fim\"\"\");\n    return 0;\n}\n";
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+
+    assert!(assembly.contains("str_0: db \"linha 1\", 10, \"linha \", 34, \"2\", 34, \" fim\", 0\n"));
+}
+
+#[test]
+fn test_visitor_mut_replaces_literals() {
+    struct ZeroToOne;
+
+    impl VisitorMut for ZeroToOne {
+        fn visit_expression_mut(&mut self, expression: &mut Expression) {
+            walk_expression_mut(self, expression);
+            if let Expression::Literal(literal) = expression {
+                if literal.value == Literal::Integer(0) {
+                    literal.value = Literal::Integer(1);
+                }
+            }
+        }
+    }
+
+    let source = r#"
+        func f(a: int) -> int {
+            var x: int = 0;
+            if (a > 0) {
+                x = a + 0;
+            }
+            while (x < 0) {
+                x = f(0) * (0 - x);
+            }
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let mut ast = parser.parse().expect("Falha na análise sintática");
+    
+    ZeroToOne.visit_program_mut(&mut ast);
+
+    let debug = format!("{:?}", ast);
+    assert!(!debug.contains("Integer(0)"));
+    assert_eq!(debug.matches("Integer(1)").count(), 7);
+}
+
+#[test]
+fn test_calling_conventions() {
+    let source = r#"
+        func inc(a: int) -> int {
+            return a + 1;
+        }
+
+        func main() -> int {
+            return inc(41);
+        }
+    "#;
+
+    let config = CompilerConfig {
+        calling_convention: CallingConvention::Windows,
+        ..CompilerConfig::default()
+    };
+    let mut compiler = Compiler::with_config(config).expect("Configuração inválida");
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    assert!(assembly.contains("inc:\n    push rbp\n    mov rbp, rsp\n    sub rsp, 80\n    push rbx\n    sub rsp, 8\n    mov rax, rcx\n    mov [rbp-8], rax\n"));
+    assert!(assembly.contains("    push 41\n    pop rcx\n    sub rsp, 32\n    call inc\n    add rsp, 32\n"));
+    assert!(!assembly.contains("pop rdi"));
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    assert!(assembly.contains("    mov rax, rdi\n    mov [rbp-8], rax\n"));
+    assert!(assembly.contains("    push 41\n    pop rdi\n    call inc\n    push rax\n"));
+    assert!(!assembly.contains("sub rsp, 32"));
+}
+
+#[test]
+fn test_infinite_loop_warning() {
+    fn warnings_for(source: &str) -> Vec<SemanticWarning> {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+        
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().expect("Falha na análise sintática");
+        
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(&ast).expect("Falha na análise semântica");
+        analyzer.warnings().to_vec()
+    }
+
+    let warnings = warnings_for("func main() -> int {\n    while (true) {}\n    return 0;\n}\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].location.line, 2);
+    assert!(warnings[0].message.contains("infinito"));
+
+    let warnings = warnings_for("func main() -> int {\n    while (true) { break; }\n    return 0;\n}\n");
+    assert!(warnings.is_empty());
+
+    // `break` de um laço interno não encerra o externo
+    let warnings = warnings_for("func main() -> int {\n    while (1 < 2) { while (true) { break; } }\n    return 0;\n}\n");
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn test_break_statement() {
+    let source = r#"
+        func main() -> int {
+            while (true) {
+                break;
+            }
+            return 0;
+        }
+    "#;
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+    assert!(assembly.contains("    je endwhile_2\n    jmp endwhile_2\n    jmp while_1\n"));
+
+    let error = compiler
+        .compile("func main() -> int {\n    break;\n    return 0;\n}\n")
+        .expect_err("'break' fora de um laço deveria falhar");
+    assert!(error.to_string().contains("'break' fora de um laço"));
+}
+
+#[test]
+fn test_char_comparison_is_bool() {
+    let source = r#"
+        func main() -> int {
+            var c: char = 'x';
+            var ok: bool = c < 'z' && c != '\n';
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+    assert!(assembly.contains("    push 120\n    pop rax\n    mov byte [rbp-1], al\n"));
+    assert!(assembly.contains("    movzx eax, byte [rbp-1]\n"));
+
+    let source = "func main() -> int {\n    var n: int = 'a' < 'b';\n    return 0;\n}\n";
+    let tokens = Lexer::new(source).tokenize().expect("Falha na análise léxica");
+    let ast = Parser::new(tokens).parse().expect("Falha na análise sintática");
+    let error = SemanticAnalyzer::new().analyze(&ast).expect_err("Deveria falhar na análise semântica");
+    assert!(error.to_string().contains("bool"), "{}", error);
+}
+
+#[test]
+fn test_operator_precedence() {
+    // Forma da árvore com parênteses explícitos, ignorando as posições
+    fn shape(expression: &Expression) -> String {
+        match expression {
+            Expression::Literal(literal) => literal.value.to_string(),
+            Expression::Identifier(identifier) => identifier.name.clone(),
+            Expression::Binary(binary) => {
+                format!("({} {} {})", shape(&binary.left), binary.operator, shape(&binary.right))
+            }
+            Expression::Unary(unary) => match unary.operator {
+                UnaryOperator::Minus => format!("(-{})", shape(&unary.operand)),
+                _ => format!("(!{})", shape(&unary.operand)),
+            },
+            other => panic!("Expressão inesperada: {:?}", other),
+        }
+    }
+
+    let cases = [
+        ("2 + 3 % 4 * 5", "(2 + ((3 % 4) * 5))"),
+        ("2 * 3 + 4 % 5 - 6 / 7", "(((2 * 3) + (4 % 5)) - (6 / 7))"),
+        ("1 - 2 - 3", "((1 - 2) - 3)"),
+        ("8 / 4 % 3 * 2", "(((8 / 4) % 3) * 2)"),
+        ("-2 * 3", "((-2) * 3)"),
+        ("2 * -3", "(2 * (-3))"),
+        ("-a % -b", "((-a) % (-b))"),
+        ("- -a * b", "((-(-a)) * b)"),
+        ("!a && b", "((!a) && b)"),
+        ("a + 1 < b * 2 == c", "(((a + 1) < (b * 2)) == c)"),
+        ("a || b && c", "(a || (b && c))"),
+    ];
+
+    for (source, expected) in cases {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression().expect("Falha na análise sintática");
+        assert_eq!(shape(&expression), expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_empty_statements() {
+    let source = r#"
+        func main() -> int {
+            var c: bool = false;
+            ;;
+            while (c);
+            return 0;;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let Statement::Function(main) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    assert!(matches!(main.body.statements[1], Statement::Empty(_)));
+    assert!(matches!(main.body.statements[2], Statement::Empty(_)));
+    let Statement::While(while_stmt) = &main.body.statements[3] else {
+        panic!("Esperado um while");
+    };
+    assert!(matches!(*while_stmt.body, Statement::Empty(_)));
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert!(analyzer.warnings().is_empty());
+
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+    assert!(assembly.contains(
+        "while_1:\n    mov rax, [rbp-8]\n    push rax\n    pop rax\n    cmp rax, 0\n    je endwhile_2\n    jmp while_1\nendwhile_2:\n"
+    ));
+}
+
+#[test]
+fn test_call_stack_alignment() {
+    let source = r#"
+        func seven(a: int, b: int, c: int, d: int, e: int, f: int, g: int) -> int {
+            return g;
+        }
+
+        func eight(a: int, b: int, c: int, d: int, e: int, f: int, g: int, h: int) -> int {
+            return h;
+        }
+
+        func one() -> int {
+            return 1;
+        }
+
+        func main() -> int {
+            var x: int = seven(1, 2, 3, 4, 5, 6, 7);
+            var y: int = eight(1, 2, 3, 4, 5, 6, 7, 8);
+            return 1 + one();
+        }
+    "#;
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    // Um argumento na pilha: 8 bytes de alinhamento antes dos argumentos
+    assert!(assembly.contains("    sub rsp, 8\n    push 7\n"));
+    assert!(assembly.contains("    call seven\n    add rsp, 8\n    add rsp, 8\n"));
+
+    // Dois argumentos na pilha já mantêm o alinhamento
+    assert!(assembly.contains("    push 8\n    push 7\n"));
+    assert!(assembly.contains("    call eight\n    add rsp, 16\n    push rax\n"));
+
+    // O operando esquerdo de `1 + one()` fica na pilha durante a chamada
+    assert!(assembly.contains("    push 1\n    sub rsp, 8\n    call one\n    add rsp, 8\n"));
+}
+
+#[test]
+fn test_custom_builtins() {
+    let source = r#"
+        func main() -> int {
+            var r: float = sqrt(2.0);
+            println("ok");
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let sqrt = Symbol::builtin("sqrt", vec![Type::Float], Type::Float);
+
+    let mut builtins = default_builtins();
+    builtins.push(sqrt.clone());
+    let mut analyzer = SemanticAnalyzer::with_builtins(builtins);
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    let mut codegen = CodeGenerator::new(0);
+    let assembly = codegen.generate(&ast).expect("Falha na geração de código");
+    assert!(assembly.contains("extern sqrt\nextern println\n"));
+
+    let mut analyzer = SemanticAnalyzer::with_builtins(vec![sqrt]);
+    let error = analyzer.analyze(&ast).expect_err("println não deveria estar definida");
+    assert!(error.to_string().contains("println"), "{}", error);
+}
+
+#[test]
+fn test_conditions_without_parentheses() {
+    let source = "func f(x: int) -> int {\n    if x > 0 { return 1; }\n    while x < 0 { x = x + 1; }\n    return 0;\n}\n";
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let config = ParserConfig {
+        require_paren_conditions: false,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(tokens.clone(), config);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let Statement::Function(f) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::If(if_stmt) = &f.body.statements[0] else {
+        panic!("Esperado um if");
+    };
+    assert!(matches!(&if_stmt.condition, Expression::Binary(b) if b.operator == BinaryOperator::GreaterThan));
+    assert!(matches!(*if_stmt.then_branch, Statement::Block(_)));
+    assert!(matches!(f.body.statements[1], Statement::While(_)));
+
+    let mut parser = Parser::new(tokens);
+    let error = parser.parse().expect_err("Deveria exigir parênteses");
+    assert!(error.to_string().contains("Esperado '('"), "{}", error);
+}
+
+#[test]
+fn test_string_number_comparison_message() {
+    let mut lexer = Lexer::new("var b: bool = \"a\" == 1;");
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+
+    match error {
+        CompilerError::TypeError { message, line, .. } => {
+            assert_eq!(message, "não é possível comparar string com int");
+            assert_eq!(line, Some(1));
+        }
+        other => panic!("Erro inesperado: {:?}", other),
+    }
+}
+
+#[test]
+fn test_program_json_round_trip() {
+    let source = r#"
+        /// Soma um
+        func inc(a: int) -> int {
+            return a + 1;
+        }
+
+        func main() -> int {
+            var c: char = 'x';
+            var f: float = 1.5 * 2 as float;
+            while (true) { break; }
+            return inc(41);
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let json = ast.to_json().expect("Falha ao serializar a AST");
+    let restored = Program::from_json(&json).expect("Falha ao ler a AST");
+    assert_eq!(restored, ast);
+
+    let mut compiler = Compiler::new();
+    let expected = compiler.compile(source).expect("Falha na compilação");
+    let assembly = compiler.compile_ast(restored).expect("Falha na compilação da AST");
+    assert_eq!(assembly, expected);
+
+    assert!(matches!(Program::from_json("{\"statements\": 1}"), Err(CompilerError::JsonError(_))));
+}
+
+#[test]
+fn test_shadowing_warning_is_opt_in() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            if (x > 0) {
+                var x: int = 2;
+                println_int(x);
+            }
+            return x;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert!(analyzer.warnings().is_empty());
+
+    let mut analyzer = SemanticAnalyzer::new().with_shadowing_warnings(true);
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    let warnings = analyzer.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].location.line, 5);
+    assert_eq!(warnings[0].message, "'x' sombreia a declaração na linha 3, coluna 13");
+}
+
+#[test]
+fn test_stack_arguments_beyond_registers() {
+    let source = r#"
+        func eight(a: int, b: int, c: int, d: int, e: int, f: int, g: int, h: int) -> int {
+            return g + h;
+        }
+
+        func main() -> int {
+            return eight(1, 2, 3, 4, 5, 6, 7, 8);
+        }
+    "#;
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    // Os argumentos são empilhados da direita para a esquerda; os seis
+    // primeiros vão para os registradores e os dois últimos ficam na pilha
+    assert!(assembly.contains(
+        "    push 8\n    push 7\n    push 6\n    push 5\n    push 4\n    push 3\n    push 2\n    push 1\n"
+    ));
+    assert!(assembly.contains(
+        "    pop rdi\n    pop rsi\n    pop rdx\n    pop rcx\n    pop r8\n    pop r9\n    call eight\n    add rsp, 16\n"
+    ));
+
+    // A função chamada lê os registradores e depois os argumentos da pilha,
+    // acima do rbp salvo e do endereço de retorno
+    let callee = &assembly[assembly.find("eight:\n").unwrap()..];
+    let r9 = callee.find("    mov rax, r9\n").unwrap();
+    let g = callee.find("    mov rax, [rbp+16]\n").unwrap();
+    let h = callee.find("    mov rax, [rbp+24]\n").unwrap();
+    assert!(r9 < g && g < h);
+    assert!(!callee.contains("[rbp+32]"));
+}
+
+#[test]
+fn test_analyze_all_reports_every_error() {
+    let source = r#"
+        func main() -> int {
+            var a: int = true;
+            var b: bool = 1 + 2;
+            var c: int = a + 1;
+            return c;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let mut analyzer = SemanticAnalyzer::new();
+    let errors = analyzer.analyze_all(&ast).expect_err("Esperados erros de tipo");
+
+    // `a` continua declarada após o erro: o uso em `c` não gera outro erro
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(&errors[0], CompilerError::TypeError { line: Some(3), .. }));
+    assert!(matches!(&errors[1], CompilerError::TypeError { line: Some(4), .. }));
+
+    // `analyze` continua parando no primeiro erro
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_err());
+}
+
+#[test]
+fn test_const_evaluation() {
+    let source = r#"
+        const N: int = 2 + 3;
+        const M: int = N * 2 - 1;
+        const ENABLED: bool = !(N > M);
+
+        func main() -> int {
+            return M;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    assert_eq!(analyzer.lookup("N").and_then(|s| s.value.clone()), Some(Literal::Integer(5)));
+    assert_eq!(analyzer.lookup("M").and_then(|s| s.value.clone()), Some(Literal::Integer(9)));
+    assert_eq!(analyzer.lookup("ENABLED").and_then(|s| s.value.clone()), Some(Literal::Boolean(true)));
+    assert_eq!(analyzer.lookup("main").and_then(|s| s.value.clone()), None);
+
+    let mut compiler = Compiler::new();
+    compiler.compile(source).expect("Falha na compilação");
+
+    let invalid = [
+        "var someVar: int = 1; const M: int = someVar; func main() -> int { return 0; }",
+        "func f() -> int { return 1; } const M: int = f(); func main() -> int { return 0; }",
+        "const N: int = 1; func main() -> int { N = 2; return 0; }",
+    ];
+    for source in invalid {
+        let error = compiler.compile(source).expect_err("Deveria falhar na análise semântica");
+        assert!(matches!(error, CompilerError::SemanticError { .. }), "{}", error);
+    }
+
+    let error = compiler.compile("const N: int; func main() -> int { return 0; }").unwrap_err();
+    assert!(error.to_string().contains("precisa de um inicializador"));
+}
+
+#[test]
+fn test_operator_table_matches_previous_rules() {
+    use BinaryOperator::*;
+
+    // Regras da análise de expressões binárias antes da tabela de operadores
+    fn previous_rules(operator: &BinaryOperator, left: &Type, right: &Type) -> Option<Type> {
+        let numeric = |ty: &Type| matches!(ty, Type::Int | Type::Float);
+        match operator {
+            Add | Subtract | Multiply | Divide => {
+                if *left == Type::Int && *right == Type::Int {
+                    Some(Type::Int)
+                } else if numeric(left) && numeric(right) {
+                    Some(Type::Float)
+                } else {
+                    None
+                }
+            }
+            Equal | NotEqual => {
+                let compatible = left == right || (*left == Type::Float && *right == Type::Int);
+                compatible.then_some(Type::Bool)
+            }
+            LessThan | LessThanEqual | GreaterThan | GreaterThanEqual => {
+                let comparable = (numeric(left) && numeric(right)) || (*left == Type::Char && *right == Type::Char);
+                comparable.then_some(Type::Bool)
+            }
+            And | Or => (*left == Type::Bool && *right == Type::Bool).then_some(Type::Bool),
+            Modulo => (*left == Type::Int && *right == Type::Int).then_some(Type::Int),
+        }
+    }
+
+    let operators = [
+        Add, Subtract, Multiply, Divide, Modulo, Equal, NotEqual,
+        LessThan, LessThanEqual, GreaterThan, GreaterThanEqual, And, Or,
+    ];
+    let types = [Type::Int, Type::Float, Type::Bool, Type::Char, Type::String, Type::Void];
+
+    let table = default_operators();
+    for operator in &operators {
+        for left in &types {
+            for right in &types {
+                assert_eq!(
+                    table.resolve(operator, left, right),
+                    previous_rules(operator, left, right),
+                    "{} {} {}", left, operator, right
+                );
+            }
+        }
+    }
+
+    // Novas combinações entram na tabela sem mudar o analisador
+    let mut table = default_operators();
+    table.register(Add, Type::String, Type::String, Type::String);
+    assert_eq!(table.resolve(&Add, &Type::String, &Type::String), Some(Type::String));
+    assert_eq!(OperatorTable::new().resolve(&Add, &Type::Int, &Type::Int), None);
+}
+
+/// Percorre todos os caminhos do assembly de `function` a partir da entrada
+/// e verifica que cada um termina em `ret` precedido do epílogo, sem cair
+/// no código que vem depois da função
+fn assert_all_paths_return(assembly: &str, function: &str) {
+    let start = assembly.find(&format!("\n{}:\n", function)).expect("função não encontrada") + 1;
+    // Cada função termina com uma linha em branco após o epílogo final
+    let body: Vec<&str> = assembly[start..].lines().skip(1).take_while(|line| !line.is_empty()).collect();
+    let label = |name: &str| {
+        body.iter()
+            .position(|line| *line == format!("{}:", name))
+            .unwrap_or_else(|| panic!("rótulo {} fora de {}", name, function))
+    };
+
+    let mut pending = vec![0];
+    let mut visited = std::collections::HashSet::new();
+    while let Some(mut index) = pending.pop() {
+        loop {
+            if !visited.insert(index) {
+                break;
+            }
+            let instruction = body
+                .get(index)
+                .unwrap_or_else(|| panic!("um caminho de {} passa do fim da função", function))
+                .trim();
+            if instruction == "ret" {
+                assert_eq!(body[index - 2].trim(), "mov rsp, rbp", "ret sem epílogo em {}", function);
+                assert_eq!(body[index - 1].trim(), "pop rbp", "ret sem epílogo em {}", function);
+                break;
+            }
+            match instruction.split_once(' ') {
+                Some(("jmp", target)) => index = label(target),
+                Some((jump, target)) if jump.starts_with('j') => {
+                    pending.push(label(target));
+                    index += 1;
+                }
+                _ => index += 1,
+            }
+        }
+    }
+}
+
+#[test]
+fn test_void_function_exit_paths() {
+    let source = r#"
+        func both(x: int) -> void {
+            if (x > 0) {
+                println("positivo");
+                return;
+            } else {
+                return;
+            }
+        }
+
+        func early(x: int) -> void {
+            if (x > 0) {
+                return;
+            }
+            while (x < 10) {
+                if (x == 5) {
+                    return;
+                }
+                x = x + 1;
+            }
+        }
+
+        func implicit() -> void {
+            println("sem return");
+        }
+
+        func main() -> int {
+            both(1);
+            early(2);
+            implicit();
+            return 0;
+        }
+    "#;
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    for function in ["both", "early", "implicit", "main"] {
+        assert_all_paths_return(&assembly, function);
+    }
+}
+
+#[test]
+fn test_analyzer_with_scope() {
+    use ruscompile::semantic::Scope;
+
+    fn parse(source: &str) -> Program {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+        let mut parser = Parser::new(tokens);
+        parser.parse().expect("Falha na análise sintática")
+    }
+
+    // Sem escopo inicial, `x` não está definida; semeado com `x`, o mesmo programa é válido
+    let uses_x = parse("func get() -> int { return x; }");
+
+    let mut fresh = SemanticAnalyzer::new();
+    assert!(fresh.analyze(&uses_x).is_err());
+
+    let mut seed = Scope::new();
+    seed.define(Symbol {
+        name: "x".to_string(),
+        symbol_type: Type::Int,
+        is_function: false,
+        parameters: vec![],
+        return_type: None,
+        location: None,
+        value: None,
+    })
+    .unwrap();
+    let mut seeded = SemanticAnalyzer::with_scope(seed);
+    seeded.analyze(&uses_x).expect("Falha na análise semântica");
+
+    // As definições de uma entrada ficam disponíveis para a seguinte
+    let mut first = SemanticAnalyzer::new();
+    first.analyze(&parse("var y: int = 1; func twice(n: int) -> int { return n * 2; }")).unwrap();
+    let mut second = SemanticAnalyzer::with_scope(first.take_scope());
+    second.analyze(&parse("var z: int = twice(y);")).expect("Falha na análise semântica");
+    assert_eq!(second.lookup("z").map(|symbol| &symbol.symbol_type), Some(&Type::Int));
+    assert!(second.analyze(&parse("var y: int = 2;")).is_err());
+}
+
+#[test]
+fn test_float_modulo_is_rejected() {
+    let source = r#"
+        func main() -> int {
+            var r: float = 1.5 % 2.0;
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let mut analyzer = SemanticAnalyzer::new();
+    let error = analyzer.analyze(&ast).expect_err("Deveria falhar na análise semântica");
+    assert!(matches!(error, CompilerError::TypeError { line: Some(3), .. }));
+    assert!(
+        error.to_string().contains("operação módulo requer operandos inteiros, encontrado float e float"),
+        "{}", error
+    );
+
+    // Entre inteiros, `%` continua válido
+    let mut compiler = Compiler::new();
+    assert!(compiler.compile("func main() -> int { return 7 % 3; }").is_ok());
+}
+
+#[test]
+fn test_redundant_bool_comparison_warning() {
+    let source = r#"
+        func main() -> int {
+            var flag: bool = true;
+            var a: bool = false;
+            var b: bool = true;
+            if (flag == true) {
+                println("sim");
+            }
+            if (false != a && b) {
+                println("talvez");
+            }
+            if (a == b) {
+                println("iguais");
+            }
+            return 0;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    // Desativado por padrão
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert!(analyzer.warnings().is_empty());
+
+    let mut analyzer = SemanticAnalyzer::new().with_redundant_bool_warnings(true);
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    // `a == b` não compara com um literal e não gera aviso
+    let warnings = analyzer.warnings();
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert_eq!(warnings[0].message, "comparação redundante com 'true'; use 'flag'");
+    assert_eq!(warnings[0].location.line, 6);
+    assert_eq!(warnings[1].message, "comparação redundante com 'false'; use 'a'");
+    assert_eq!(warnings[1].location.line, 9);
+
+    let source = "func main() -> int { var a: bool = true; var x: bool = (a && a) == false; return 0; }";
+    let config = CompilerConfig { warn_redundant_bool: true, ..CompilerConfig::default() };
+    let compiler = Compiler::with_config(config).expect("Configuração inválida");
+    let (_, diagnostics) = compiler.compile_str_with_diagnostics(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.ends_with("use '!(a && a)'"), "{}", diagnostics[0]);
+}
+
+#[test]
+fn test_trailing_commas() {
+    let source = "func add(a: int, b: int,) -> int { return a + b; }\nvar s: int = add(1, 2,);";
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let config = ParserConfig {
+        allow_trailing_commas: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(tokens.clone(), config);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let Statement::Function(add) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    assert_eq!(add.parameters.len(), 2);
+
+    let Statement::Declaration(decl) = &ast.statements[1] else {
+        panic!("Esperada uma declaração");
+    };
+    let Some(Expression::Call(call)) = &decl.initializer else {
+        panic!("Esperada uma chamada");
+    };
+    assert_eq!(call.arguments.len(), 2);
+
+    // Uma vírgula sozinha continua inválida
+    let mut lexer = Lexer::new("var s: int = add(,);");
+    let config = ParserConfig { allow_trailing_commas: true, ..ParserConfig::default() };
+    assert!(Parser::with_config(lexer.tokenize().unwrap(), config).parse().is_err());
+
+    // Desativado por padrão
+    let mut parser = Parser::new(tokens);
+    let error = parser.parse().expect_err("Deveria falhar na análise sintática");
+    assert!(matches!(error, CompilerError::SyntaxError { line: 1, .. }));
+}
+
+#[test]
+fn test_end_of_file_error_location() {
+    // Sem quebra de linha no final: o erro aponta logo após o `;`
+    let source = "func main() -> int {\n    return 0;";
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    
+    let mut parser = Parser::new(tokens);
+    let error = parser.parse().expect_err("Deveria falhar na análise sintática");
+    assert!(matches!(
+        &error,
+        CompilerError::SyntaxError { line: 2, column: 14, message } if message == "Esperado '}' antes do fim do arquivo"
+    ), "{}", error);
+
+    let mut lexer = Lexer::new("var x: int = 1 +");
+    let mut parser = Parser::new(lexer.tokenize().unwrap());
+    let error = parser.parse().expect_err("Deveria falhar na análise sintática");
+    assert!(matches!(error, CompilerError::SyntaxError { line: 1, column: 17, .. }), "{}", error);
+}
+
+#[test]
+fn test_labeled_break_and_continue() {
+    let source = r#"
+        func main() -> int {
+            var i: int = 0;
+            outer: while (true) {
+                while (true) {
+                    i = i + 1;
+                    if (i < 3) { continue outer; }
+                    break outer;
+                }
+            }
+            return i;
+        }
+    "#;
+
+    let mut compiler = Compiler::new();
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+    // `continue outer` volta à condição do laço externo e `break outer` salta
+    // para o fim dele, não do interno
+    assert!(assembly.contains("    jmp while_1\n"));
+    assert!(assembly.contains("    jmp endwhile_2\n    jmp while_3\nendwhile_4:\n"));
+
+    // O laço rotulado termina por `break outer`: não é um laço infinito
+    let mut parser = Parser::new(Lexer::new(source).tokenize().unwrap());
+    let ast = parser.parse().expect("Falha na análise sintática");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert!(analyzer.warnings().is_empty());
+
+    let error = compiler
+        .compile("func main() -> int {\n    while (true) {\n        break inner;\n    }\n    return 0;\n}\n")
+        .expect_err("Rótulo indefinido deveria falhar");
+    assert!(matches!(
+        &error,
+        CompilerError::SemanticError { line: Some(3), message, .. } if message == "Rótulo de laço 'inner' não definido"
+    ), "{}", error);
+
+    let error = compiler
+        .compile("func main() -> int {\n    continue;\n    return 0;\n}\n")
+        .expect_err("'continue' fora de um laço deveria falhar");
+    assert!(error.to_string().contains("'continue' fora de um laço"));
+}
+
+#[test]
+fn test_var_type_inference() {
+    let source = r#"
+        var x = 1.5;
+        var flag = true;
+        var n = 2 * 3;
+        var c = 'a';
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+    match &ast.statements[0] {
+        Statement::Declaration(decl) => assert_eq!(decl.var_type, Type::Inferred),
+        other => panic!("Esperada declaração, encontrado {:?}", other),
+    }
+
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert_eq!(analyzer.lookup("x").map(|s| s.symbol_type.clone()), Some(Type::Float));
+    assert_eq!(analyzer.lookup("flag").map(|s| s.symbol_type.clone()), Some(Type::Bool));
+    assert_eq!(analyzer.lookup("n").map(|s| s.symbol_type.clone()), Some(Type::Int));
+    assert_eq!(analyzer.lookup("c").map(|s| s.symbol_type.clone()), Some(Type::Char));
+
+    // O tipo inferido vale para os usos seguintes
+    let mut compiler = Compiler::new();
+    let error = compiler
+        .compile("func main() -> int {\n    var x = 1.5;\n    var b: bool = x;\n    return 0;\n}\n")
+        .expect_err("float em variável bool deveria falhar");
+    assert!(error.to_string().contains("esperado bool, encontrado float"), "{}", error);
+
+    // Sem tipo nem inicializador não há de onde inferir
+    let error = compiler
+        .compile("func main() -> int {\n    var y;\n    return 0;\n}\n")
+        .expect_err("'var y;' deveria falhar");
+    assert!(matches!(
+        &error,
+        CompilerError::SemanticError { line: Some(2), message, .. } if message.contains("Não é possível inferir o tipo de 'y'")
+    ), "{}", error);
+
+    // Um `char` inferido ocupa 1 byte na pilha, como um declarado
+    let assembly = compiler
+        .compile("func main() -> int {\n    var c = 'a';\n    return 0;\n}\n")
+        .expect("Falha na compilação");
+    assert!(assembly.contains("mov byte [rbp-1], al"), "{}", assembly);
+}
+
+#[test]
+fn test_annotate_assembly() {
+    let source = "func main() -> int {\n    var x: int = 2;\n    return x + 1;\n}\n";
+
+    let config = CompilerConfig { annotate_assembly: true, ..CompilerConfig::default() };
+    let mut compiler = Compiler::with_config(config).expect("Configuração inválida");
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+
+    assert!(assembly.contains("    ; linha 1: func main() -> int {\nmain:\n"), "{}", assembly);
+    assert!(assembly.contains("    ; linha 2: var x: int = 2;\n"));
+    // O comentário vem logo antes das instruções do `return`
+    let annotation = assembly.find("    ; linha 3: return x + 1;\n").expect("Comentário do return ausente");
+    let load = assembly.find("    mov rax, [rbp-8]\n").expect("Carga de x ausente");
+    assert!(annotation < load);
+
+    // Desativado por padrão
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    assert!(!assembly.contains("; linha"));
+}
+
+#[test]
+fn test_self_assignment_warning() {
+    let source = r#"
+        func main() -> int {
+            var x: int = 1;
+            var y: int = 2;
+            x = x;
+            x = x + 1;
+            y = (x = x);
+            x = y;
+            return x;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let mut analyzer = SemanticAnalyzer::new().with_self_assignment_warnings(true);
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+
+    // `x = x + 1` e `x = y` não geram aviso
+    let warnings = analyzer.warnings();
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert_eq!(warnings[0].message, "atribuição de 'x' a si mesma não tem efeito");
+    assert_eq!(warnings[0].location.line, 5);
+    assert_eq!(warnings[1].location.line, 7);
+
+    // Desativado por padrão
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert!(analyzer.warnings().is_empty());
+
+    let config = CompilerConfig { warn_self_assignment: true, ..CompilerConfig::default() };
+    let compiler = Compiler::with_config(config).expect("Configuração inválida");
+    let (_, diagnostics) = compiler.compile_str_with_diagnostics("func main() -> int { var a: int = 0; a = a; return a; }");
+    assert_eq!(diagnostics.len(), 1);
+}
+
+#[test]
+fn test_constant_conditions_are_not_materialized() {
+    let source = "func main() -> int {\n    if (true) { return 1; } else { return 2; }\n}\n";
+
+    let config = CompilerConfig { _optimization_level: 1, ..CompilerConfig::default() };
+    let mut compiler = Compiler::with_config(config.clone()).expect("Configuração inválida");
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+    assert!(!assembly.contains("cmp"), "{}", assembly);
+    assert!(!assembly.contains("else_"));
+    assert!(assembly.contains("    push 1\n"));
+    assert!(!assembly.contains("    push 2\n"));
+
+    // `while (false)` some e `while (true)` não testa a condição
+    let source = "func main() -> int {\n    while (false) { return 3; }\n    while (true) { break; }\n    return 0;\n}\n";
+    let assembly = compiler.compile(source).expect("Falha na compilação");
+    assert!(!assembly.contains("cmp"), "{}", assembly);
+    assert!(!assembly.contains("    push 3\n"));
+    assert!(assembly.contains("    jmp endwhile_2\n    jmp while_1\nendwhile_2:\n"));
+
+    // Sem otimização a condição continua sendo avaliada
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    assert!(assembly.contains("    cmp rax, 0\n"));
+}
+
+#[test]
+fn test_float_literal_display_round_trip() {
+    assert_eq!(Literal::Float(1.0).to_string(), "1.0");
+    assert_eq!(Literal::Float(-3.0).to_string(), "-3.0");
+    assert_eq!(Literal::Float(2.5).to_string(), "2.5");
+    assert_eq!(Literal::Float(1e20).to_string(), "100000000000000000000.0");
+
+    let parse = |source: &str| {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+        Parser::new(tokens).parse().expect("Falha na análise sintática")
+    };
+
+    // Reescrever a declaração a partir da AST mantém o literal como float
+    let ast = parse("var x: float = 1.0;");
+    let Statement::Declaration(decl) = &ast.statements[0] else {
+        panic!("Esperada uma declaração");
+    };
+    let Some(Expression::Literal(literal)) = &decl.initializer else {
+        panic!("Esperado um literal");
+    };
+    let unparsed = format!("var y = {};", literal.value);
+    assert_eq!(unparsed, "var y = 1.0;");
+
+    let ast = parse(&unparsed);
+    let Statement::Declaration(decl) = &ast.statements[0] else {
+        panic!("Esperada uma declaração");
+    };
+    assert!(matches!(
+        &decl.initializer,
+        Some(Expression::Literal(LiteralExpression { value: Literal::Float(value), .. })) if *value == 1.0
+    ));
+
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert_eq!(analyzer.lookup("y").map(|s| s.symbol_type.clone()), Some(Type::Float));
+}
+
+#[test]
+fn test_function_used_as_value_in_operation() {
+    let mut compiler = Compiler::new();
+
+    for source in [
+        "func main() -> int {\n    var x: int = main + 1;\n    return x;\n}\n",
+        "func main() -> int {\n    if (main == 1) { return 1; }\n    return 0;\n}\n",
+    ] {
+        let error = compiler.compile(source).expect_err("Função usada como valor deveria falhar");
+        assert!(matches!(
+            &error,
+            CompilerError::TypeError { line: Some(2), message, .. }
+                if message == "'main' é uma função e não pode ser usada como valor"
+        ), "{}", error);
+    }
+
+    // Comparar duas funções com a mesma assinatura continua permitido
+    let source = "func f() -> int { return 1; }\nfunc main() -> int {\n    var same: bool = f == f;\n    return 0;\n}\n";
+    assert!(compiler.compile(source).is_ok());
+}
+
+#[test]
+fn test_lint_aggregates_diagnostics() {
+    let source = r#"
+        func helper() -> int {
+            var unused: int = 1;
+            var x: int = 2;
+            x = x;
+            return x;
+        }
+    "#;
+
+    // Sem `main` e sem habilitar nada na configuração
+    let diagnostics = Compiler::new().lint(source);
+    assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+    assert!(diagnostics.iter().all(|diagnostic| diagnostic.severity == Severity::Warning));
+    assert_eq!(diagnostics[0].message, "Variável 'unused' declarada mas não utilizada");
+    assert_eq!(diagnostics[0].line, Some(3));
+    assert_eq!(diagnostics[1].message, "atribuição de 'x' a si mesma não tem efeito");
+    assert_eq!(diagnostics[1].line, Some(5));
+
+    // Erros também são reportados, com severidade de erro
+    let diagnostics = Compiler::new().lint("func f() -> int {\n    return y;\n}\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].line, Some(2));
+}
+
+#[test]
+fn test_require_braces() {
+    let parse = |source: &str, require_braces: bool| {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+        let config = ParserConfig { require_braces, ..ParserConfig::default() };
+        Parser::with_config(tokens, config).parse()
+    };
+
+    let source = "func f(a: bool) -> int {\n    if (a) return 1;\n    return 0;\n}\n";
+    let error = parse(source, true).expect_err("Corpo sem chaves deveria falhar");
+    assert!(matches!(
+        &error,
+        CompilerError::SyntaxError { line: 2, column: 12, message } if message == "O corpo de 'if' precisa estar entre chaves"
+    ), "{}", error);
+
+    // Desativado, o corpo continua podendo ser um comando qualquer
+    let ast = parse(source, false).expect("Falha na análise sintática");
+    let Statement::Function(f) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::If(if_stmt) = &f.body.statements[0] else {
+        panic!("Esperado um if");
+    };
+    assert!(matches!(*if_stmt.then_branch, Statement::Return(_)));
+
+    let error = parse("func f(a: bool) {\n    while (a) a = false;\n}\n", true).unwrap_err();
+    assert!(error.to_string().contains("O corpo de 'while' precisa estar entre chaves"), "{}", error);
+    let error = parse("func f(a: bool) {\n    if (a) { } else a = false;\n}\n", true).unwrap_err();
+    assert!(error.to_string().contains("O corpo de 'else' precisa estar entre chaves"), "{}", error);
+
+    // `else if` continua permitido
+    assert!(parse("func f(a: bool, b: bool) {\n    if (a) { } else if (b) { } else { }\n}\n", true).is_ok());
+}
+
+#[test]
+fn test_empty_function_bodies() {
+    // Função `void` vazia gera apenas prólogo, epílogo e `ret`
+    let source = "func g() {}\n\nfunc main() -> int {\n    g();\n    return 0;\n}\n";
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    assert!(
+        assembly.contains("g:\n    push rbp\n    mov rbp, rsp\n    sub rsp, 80\n    mov rsp, rbp\n    pop rbp\n    ret\n"),
+        "{}",
+        assembly
+    );
+
+    // Função com tipo de retorno e corpo vazio é rejeitada
+    let error = Compiler::new().compile("func f() -> int {}\n").unwrap_err();
+    assert!(matches!(
+        &error,
+        CompilerError::SemanticError { line: Some(1), message, .. } if message == "Função 'f' tem o corpo vazio, mas deve retornar int"
+    ), "{}", error);
+
+    // O mesmo vale quando algum caminho termina sem `return`
+    let error = Compiler::new()
+        .compile("func f(a: bool) -> int {\n    if (a) {\n        return 1;\n    }\n}\n")
+        .unwrap_err();
+    assert!(error.to_string().contains("Função 'f' deve retornar int em todos os caminhos"), "{}", error);
+    assert!(Compiler::new()
+        .compile("func f(a: bool) -> int {\n    if (a) {\n        return 1;\n    } else {\n        return 0;\n    }\n}\n\nfunc main() -> int {\n    return f(true);\n}\n")
+        .is_ok());
+}
+
+#[test]
+fn test_error_codes() {
+    let error = Compiler::new()
+        .compile("func main() -> int {\n    var x: int = true;\n    return 0;\n}\n")
+        .unwrap_err();
+    assert!(matches!(error, CompilerError::TypeError { .. }), "{}", error);
+    assert_eq!(error.code(), "E-TYPE-001");
+
+    let error = Compiler::new().compile("func main() -> int {\n    return 0\n}\n").unwrap_err();
+    assert!(matches!(error, CompilerError::SyntaxError { .. }), "{}", error);
+    assert_eq!(error.code(), "E-SYN-001");
+
+    assert_eq!(CompilerError::lexical(1, 1, "x").code(), "E-LEX-001");
+    assert_eq!(CompilerError::semantic("x").code(), "E-SEM-001");
+}
+
+#[test]
+fn test_deeply_nested_expression_parses() {
+    let depth = 500;
+    let source = format!(
+        "func main() -> int {{\n    return {}1{};\n}}\n",
+        "(".repeat(depth),
+        " + 1)".repeat(depth)
+    );
+
+    let start = std::time::Instant::now();
+    let mut lexer = Lexer::new(&source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    let ast = Parser::new(tokens).parse().expect("Falha na análise sintática");
+    assert!(start.elapsed() < std::time::Duration::from_secs(1), "{:?}", start.elapsed());
+
+    let Statement::Function(f) = &ast.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::Return(ret) = &f.body.statements[0] else {
+        panic!("Esperado um return");
+    };
+    let mut nesting = 0;
+    let mut expr = ret.value.as_ref().unwrap();
+    while let Expression::Binary(binary) = expr {
+        nesting += 1;
+        expr = &binary.left;
+    }
+    assert_eq!(nesting, depth);
+}
+
+/// Forma parentizada de uma expressão, para comparar árvores sem depender
+/// das posições
+fn render(expr: &Expression) -> String {
+    match expr {
+        Expression::Literal(literal) => format!("{}", literal.value),
+        Expression::Identifier(identifier) => identifier.name.clone(),
+        Expression::Binary(binary) => {
+            format!("({} {} {})", binary.operator, render(&binary.left), render(&binary.right))
+        }
+        Expression::Unary(unary) => format!("({:?} {})", unary.operator, render(&unary.operand)),
+        Expression::Call(call) => {
+            let arguments: Vec<String> = call.arguments.iter().map(render).collect();
+            format!("(call {} [{}])", call.function, arguments.join(", "))
+        }
+        Expression::IndirectCall(call) => {
+            let arguments: Vec<String> = call.arguments.iter().map(render).collect();
+            format!("(call {} [{}])", render(&call.callee), arguments.join(", "))
+        }
+        Expression::Assignment(assign) => format!("(= {} {})", assign.target, render(&assign.value)),
+        Expression::Cast(cast) => format!("(as {} {})", render(&cast.expression), cast.target_type),
+        Expression::Error(_) => "<erro>".to_string(),
+    }
+}
+
+#[test]
+fn test_expression_parse_trees() {
+    // Árvores produzidas pelo parser recursivo anterior (um método por nível
+    // de precedência); o parser por tabela precisa reproduzi-las
+    let battery = [
+        ("a + b * c - d / e % f", "(- (+ a (* b c)) (% (/ d e) f))"),
+        ("a - b - c", "(- (- a b) c)"),
+        ("a / b * c % d", "(% (* (/ a b) c) d)"),
+        ("a || b && c", "(|| a (&& b c))"),
+        ("a && b || c && d", "(|| (&& a b) (&& c d))"),
+        ("a == b != c", "(!= (== a b) c)"),
+        ("a < b == c > d", "(== (< a b) (> c d))"),
+        ("a <= b + 1 >= c * 2", "(>= (<= a (+ b 1)) (* c 2))"),
+        ("1 + 2 * 3 == 7 || false", "(|| (== (+ 1 (* 2 3)) 7) false)"),
+        ("-a * -b", "(* (Minus a) (Minus b))"),
+        ("!a && !!b", "(&& (Not a) (Not (Not b)))"),
+        ("- - - 3", "(Minus (Minus (Minus 3)))"),
+        ("-x as float", "(Minus (as x float))"),
+        ("(-x) as float", "(as (Minus x) float)"),
+        ("1 as int as float", "(as (as 1 int) float)"),
+        ("f(1, 2)(3) as int + 4", "(+ (as (call (call f [1, 2]) [3]) int) 4)"),
+        ("(a)(b)", "(call a [b])"),
+        ("f()", "(call f [])"),
+        ("g(x = 1, (y))", "(call g [(= x 1), y])"),
+        ("x = y = 3 + 4", "(= x (= y (+ 3 4)))"),
+        ("(x = 1) + 2", "(+ (= x 1) 2)"),
+        ("x = (y = 2)", "(= x (= y 2))"),
+        ("((a + b) * (c - d)) / e", "(/ (* (+ a b) (- c d)) e)"),
+        ("!(a || b) && c", "(&& (Not (|| a b)) c)"),
+        ("a * (b)", "(* a b)"),
+        ("-(a + b) * c", "(* (Minus (+ a b)) c)"),
+        ("a - (b - c)", "(- a (- b c))"),
+        ("(((1)))", "1"),
+    ];
+
+    for (source, expected) in battery {
+        let source = format!("func main() -> int {{\n    var r: int = {};\n    return 0;\n}}\n", source);
+        let tokens = Lexer::new(&source).tokenize().expect("Falha na análise léxica");
+        let ast = Parser::new(tokens).parse().expect("Falha na análise sintática");
+        let Statement::Function(f) = &ast.statements[0] else {
+            panic!("Esperada uma função");
+        };
+        let Statement::Declaration(decl) = &f.body.statements[0] else {
+            panic!("Esperada uma declaração");
+        };
+        assert_eq!(render(decl.initializer.as_ref().unwrap()), expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_return_from_nested_blocks() {
+    let source = r#"
+        func f(a: int) -> int {
+            var x: int = 1;
+            {
+                var y: int = 2;
+                {
+                    var x: int = a + y;
+                    return x;
+                }
+            }
+        }
+
+        func main() -> int {
+            return f(3);
+        }
+    "#;
+
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    let start = assembly.find("f:\n").unwrap();
+    let body = &assembly[start..assembly[start..].find("main:").unwrap() + start];
+
+    // Os locais dos blocos ficam no quadro da função: `a` em rbp-8, `x` em
+    // rbp-16, `y` em rbp-24 e o `x` interno em rbp-32
+    assert!(body.contains("    mov rax, [rbp-8]\n    push rax\n    mov rax, [rbp-24]\n"), "{}", body);
+    assert!(body.contains("    add rax, rbx\n    push rax\n    pop rax\n    mov [rbp-32], rax\n"), "{}", body);
+
+    // O `return` devolve o `x` interno com o epílogo padrão da função
+    assert!(
+        body.contains("    mov rax, [rbp-32]\n    push rax\n    pop rax\n    lea rsp, [rbp-88]\n    pop rbx\n    mov rsp, rbp\n    pop rbp\n    ret\n"),
+        "{}",
+        body
+    );
+    assert!(!body.contains("add rsp"), "{}", body);
+}
+
+#[test]
+fn test_strict_types() {
+    let source = "func main() -> int {\n    var x: float = 3;\n    return 0;\n}\n";
+    let strict = || {
+        let config = CompilerConfig { strict_types: true, ..CompilerConfig::default() };
+        Compiler::with_config(config).unwrap()
+    };
+
+    // Por padrão, o int é convertido em float implicitamente
+    assert!(Compiler::new().compile(source).is_ok());
+
+    let error = strict().compile(source).unwrap_err();
+    assert!(matches!(
+        &error,
+        CompilerError::TypeError { line: Some(2), message, .. } if message == "Tipo incompatível: esperado float, encontrado int"
+    ), "{}", error);
+
+    // A conversão explícita continua permitida
+    assert!(strict().compile("func main() -> int {\n    var x: float = 3 as float;\n    return 0;\n}\n").is_ok());
+
+    // Vale também para argumentos
+    let error = strict()
+        .compile("func f(x: float) {}\n\nfunc main() -> int {\n    f(1);\n    return 0;\n}\n")
+        .unwrap_err();
+    assert!(matches!(error, CompilerError::TypeError { .. }), "{}", error);
+}
+
+#[test]
+fn test_calling_a_variable() {
+    let error = Compiler::new()
+        .compile("func main() -> int {\n    var x: int = 1;\n    x();\n    return 0;\n}\n")
+        .unwrap_err();
+    assert!(matches!(
+        &error,
+        CompilerError::SemanticError { line: Some(3), message, .. } if message == "'x' é do tipo int e não pode ser chamada"
+    ), "{}", error);
+}
+
+#[test]
+fn test_position_independent_code() {
+    let source = "var total: int = 0;\n\nfunc main() -> int {\n    println(\"oi\");\n    total = total + 1;\n    return total;\n}\n";
+    let pie = |enabled: bool| {
+        let config = CompilerConfig { pie: enabled, ..CompilerConfig::default() };
+        Compiler::with_config(config).unwrap().compile(source).expect("Falha na compilação")
+    };
+
+    let assembly = pie(true);
+    assert!(assembly.contains("    lea rax, [rel str_0]\n    push rax\n"), "{}", assembly);
+    assert!(assembly.contains("mov rax, [rel total]"), "{}", assembly);
+    assert!(assembly.contains("mov [rel total], rax"), "{}", assembly);
+    assert!(assembly.contains("call println wrt ..plt"), "{}", assembly);
+    assert!(!assembly.contains("push str_0"), "{}", assembly);
+
+    // Sem a opção, as referências continuam absolutas
+    let assembly = pie(false);
+    assert!(assembly.contains("    push str_0\n"), "{}", assembly);
+    assert!(assembly.contains("mov rax, [total]"), "{}", assembly);
+    assert!(!assembly.contains("[rel "), "{}", assembly);
+    assert!(!assembly.contains("wrt ..plt"), "{}", assembly);
+}
+
+#[test]
+fn test_scope_resolution_across_nested_blocks() {
+    fn analyze_all(source: &str) -> (SemanticAnalyzer, Result<(), Vec<CompilerError>>) {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().expect("Falha na análise léxica");
+        let program = Parser::new(tokens).parse().expect("Falha na análise sintática");
+        let mut analyzer = SemanticAnalyzer::new();
+        let result = analyzer.analyze_all(&program);
+        (analyzer, result)
+    }
+
+    // Sombreamento em blocos aninhados: o nome interno vale só no bloco, e
+    // o externo volta a ser visível (com o seu tipo) ao sair dele
+    let (analyzer, result) = analyze_all(
+        r#"
+        var g: int = 1;
+        func f(p: bool) -> int {
+            var x: int = g;
+            {
+                var x: bool = p;
+                {
+                    var y: bool = x && p;
+                }
+            }
+            return x + g;
+        }
+    "#,
+    );
+    assert!(result.is_ok(), "{:?}", result);
+    assert!(analyzer.lookup("g").is_some());
+    assert!(analyzer.lookup("f").is_some());
+    assert!(analyzer.lookup("x").is_none());
+    assert!(analyzer.lookup("p").is_none());
+
+    // Nomes de um bloco ou de parâmetros não vazam, nem após um erro
+    let (analyzer, result) = analyze_all(
+        r#"
+        func f(p: int, p: int) -> int { return p; }
+        func h() -> int {
+            {
+                var y: int = 1;
+                var z: int = true;
+            }
+            return y;
+        }
+    "#,
+    );
+    let errors = result.unwrap_err();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(errors[0].to_string().contains("parâmetro 'p' duplicado"), "{}", errors[0]);
+    assert!(errors[2].to_string().contains("'y'"), "{}", errors[2]);
+    assert!(analyzer.lookup("p").is_none());
+    assert!(analyzer.lookup("y").is_none());
+    assert!(analyzer.lookup("h").is_some());
+}
+
+#[test]
+fn test_nested_function_rejected_by_analyzer_and_codegen() {
+    let source = "func main() -> int {\n    func inner() -> int {\n        return 1;\n    }\n    return inner();\n}\n";
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    let ast = Parser::new(tokens).parse().expect("Falha na análise sintática");
+
+    let error = SemanticAnalyzer::new().analyze(&ast).unwrap_err();
+    assert!(matches!(&error, CompilerError::SemanticError { line: Some(2), .. }), "{}", error);
+    assert!(error.to_string().contains("funções aninhadas não são suportadas"), "{}", error);
+
+    // Sem a análise, a geração de código também recusa em vez de emitir o
+    // rótulo global no meio de `main`
+    let error = CodeGenerator::new(0).generate(&ast).unwrap_err();
+    assert!(matches!(&error, CompilerError::CodeGenError { message } if message == "funções aninhadas não são suportadas: 'inner'"), "{}", error);
+    let error = LlvmGenerator::new().generate(&ast).unwrap_err();
+    assert!(error.to_string().contains("funções aninhadas não são suportadas: 'inner'"), "{}", error);
+}
+
+#[test]
+fn test_elif_keyword() {
+    let parse = |source: &str, elif_keyword: bool| {
+        let config = LexerConfig { elif_keyword, ..LexerConfig::default() };
+        let tokens = Lexer::with_config(source, config).tokenize().expect("Falha na análise léxica");
+        Parser::new(tokens).parse()
+    };
+
+    // `elif` fica na mesma coluna do `if` de `else if` (e o `(b)` também),
+    // então as árvores só diferem no comprimento da posição do `if` interno
+    let else_if = "func f(a: bool, b: bool) {\n    if (a) { } else if   (b) { } else { }\n}\n";
+    let elif = "func f(a: bool, b: bool) {\n    if (a) { }      elif (b) { } else { }\n}\n";
+    let expected = parse(else_if, true).expect("Falha na análise sintática");
+    let mut actual = parse(elif, true).expect("Falha na análise sintática");
+    if let Statement::Function(f) = &mut actual.statements[0] {
+        if let Statement::If(if_stmt) = &mut f.body.statements[0] {
+            if let Some(Statement::If(nested)) = if_stmt.else_branch.as_deref_mut() {
+                assert_eq!(nested.location.length, "elif".len());
+                nested.location.length = "if".len();
+            }
+        }
+    }
+    assert_eq!(actual, expected);
+
+    let Statement::Function(f) = &expected.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    let Statement::If(if_stmt) = &f.body.statements[0] else {
+        panic!("Esperado um if");
+    };
+    assert!(matches!(if_stmt.else_branch.as_deref(), Some(Statement::If(_))));
+
+    // Desativado, `elif` continua sendo um identificador comum
+    assert!(parse(elif, false).is_err());
+    let tokens = Lexer::new("var elif: int = 1;").tokenize().unwrap();
+    assert_eq!(tokens[1].token, Token::Identifier("elif".to_string()));
+}
+
+#[test]
+fn test_parser_recovery_keeps_error_nodes_in_place() {
+    let source = r#"func main() -> int {
+    var a: int = 1;
+    var b: int = a + * 2;
+    print(a, * 2, b);
+    var = 3;
+    return a;
+}
+"#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    let (program, errors) = Parser::new(tokens).parse_with_recovery();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+
+    // A função continua inteira: cada trecho com erro ocupa o seu lugar
+    assert_eq!(program.statements.len(), 1);
+    let Statement::Function(f) = &program.statements[0] else {
+        panic!("Esperada uma função");
+    };
+    assert_eq!(f.body.statements.len(), 5);
+
+    let Statement::Declaration(b) = &f.body.statements[1] else {
+        panic!("Esperada a declaração de 'b'");
+    };
+    assert_eq!(b.name, "b");
+    let Some(Expression::Error(error)) = &b.initializer else {
+        panic!("Esperado um Expression::Error, encontrado {:?}", b.initializer);
+    };
+    assert_eq!((error.location.line, error.location.column), (3, 18));
+
+    // Um argumento com erro não derruba a chamada nem os outros argumentos
+    let Statement::Expression(call) = &f.body.statements[2] else {
+        panic!("Esperada a chamada");
+    };
+    let Expression::Call(call) = &call.expression else {
+        panic!("Esperada uma chamada, encontrado {:?}", call.expression);
+    };
+    assert_eq!(call.arguments.len(), 3);
+    assert!(matches!(call.arguments[1], Expression::Error(_)));
+    assert!(matches!(&call.arguments[2], Expression::Identifier(id) if id.name == "b"));
+
+    assert!(matches!(f.body.statements[3], Statement::Error(_)));
+    assert!(matches!(f.body.statements[4], Statement::Return(_)));
+
+    // As fases seguintes recusam a árvore incompleta em vez de gerar código
+    assert!(SemanticAnalyzer::new().analyze(&program).is_err());
+    assert!(CodeGenerator::new(0).generate(&program).is_err());
+}
+
+#[test]
+fn test_entry_point() {
+    let source = "func helper() -> int {\n    return 1;\n}\n\nfunc run() -> int {\n    return helper();\n}\n";
+    let compile = |entry_point: Option<&str>| {
+        let config = CompilerConfig {
+            entry_point: entry_point.map(str::to_string),
+            ..CompilerConfig::default()
+        };
+        Compiler::with_config(config).unwrap().compile(source)
+    };
+
+    let assembly = compile(Some("run")).expect("Falha na compilação");
+    assert!(assembly.contains("global _start\n"), "{}", assembly);
+    assert!(assembly.contains("\n_start:\n    call run\n"), "{}", assembly);
+    assert!(!assembly.contains("call main"), "{}", assembly);
+
+    // Sem ponto de entrada: objeto de biblioteca, sem _start
+    let assembly = compile(None).expect("Falha na compilação");
+    assert!(!assembly.contains("_start"), "{}", assembly);
+    assert!(assembly.contains("global helper\nglobal run\n"), "{}", assembly);
+
+    // O ponto de entrada pedido precisa existir
+    let error = compile(Some("main")).unwrap_err();
+    assert!(error.to_string().contains("Função 'main' não encontrada"), "{}", error);
+
+    // Globais calculadas em tempo de execução dependem do _start
+    let config = CompilerConfig { entry_point: None, ..CompilerConfig::default() };
+    let error = Compiler::with_config(config)
+        .unwrap()
+        .compile("var x: int = 1;\nvar y: int = x + 1;\n\nfunc f() -> int {\n    return y;\n}\n")
+        .unwrap_err();
+    assert!(error.to_string().contains("exigem um ponto de entrada"), "{}", error);
+}
+
+#[test]
+fn test_callee_saved_registers() {
+    let source = r#"
+        func add(a: int, b: int) -> int {
+            if (a > b) {
+                return a - b;
+            }
+            return a + b;
+        }
+
+        func id(a: int) -> int {
+            return a;
+        }
+
+        func main() -> int {
+            return add(id(1), 2);
+        }
+    "#;
+
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    let function = |name: &str| {
+        let start = assembly.find(&format!("{}:\n", name)).unwrap();
+        let end = assembly[start..].find("\n\n").map_or(assembly.len(), |end| start + end + 1);
+        &assembly[start..end]
+    };
+
+    // `add` usa rbx nas operações binárias: empilhado abaixo dos locais no
+    // prólogo (com rsp realinhado) e restaurado antes de cada `ret`
+    let add = function("add");
+    assert!(add.starts_with("add:\n    push rbp\n    mov rbp, rsp\n    sub rsp, 80\n    push rbx\n    sub rsp, 8\n"), "{}", add);
+    let epilogue = "    lea rsp, [rbp-88]\n    pop rbx\n    mov rsp, rbp\n    pop rbp\n    ret\n";
+    assert_eq!(add.matches("ret\n").count(), 3, "{}", add);
+    assert_eq!(add.matches(epilogue).count(), 3, "{}", add);
+
+    // `id` não usa rbx e mantém o quadro sem registradores salvos
+    let id = function("id");
+    assert!(id.starts_with("id:\n    push rbp\n    mov rbp, rsp\n    sub rsp, 80\n    mov rax, rdi\n"), "{}", id);
+    assert!(!id.contains("rbx"), "{}", id);
+    assert!(!assembly.contains("<epílogo>"), "{}", assembly);
+}
+
+#[test]
+fn test_validate_file() {
+    let directory = std::env::temp_dir();
+    let path = |name: &str| directory.join(format!("ruscompile_validate_{}_{}.src", name, std::process::id()));
+
+    let good = path("ok");
+    std::fs::write(&good, "func main() -> int {\n    var x: int = 1;\n    return x;\n}\n").unwrap();
+    let bad = path("erro");
+    std::fs::write(&bad, "func main() -> int {\n    var x: int = 1;\n    return y;\n}\n").unwrap();
+
+    let compiler = Compiler::new();
+    let good_result = compiler.validate_file(good.to_str().unwrap());
+    let bad_result = compiler.validate_file(bad.to_str().unwrap());
+    let _ = std::fs::remove_file(&good);
+    let _ = std::fs::remove_file(&bad);
+
+    assert!(good_result.is_ok(), "{:?}", good_result);
+    let error = bad_result.unwrap_err();
+    assert!(matches!(error, CompilerError::SemanticError { line: Some(3), .. }), "{}", error);
+
+    let error = compiler.validate_file("/caminho/que/nao/existe.src").unwrap_err();
+    assert!(matches!(error, CompilerError::FileReadError(..)));
+}
+
+#[test]
+fn test_frame_size_grows_with_expression_depth() {
+    // `1 + (1 + (1 + ...))`: cada nível deixa o operando esquerdo na pilha
+    // enquanto o direito é calculado
+    fn frame_size(depth: usize) -> i32 {
+        let expression = format!("{}1{}", "1 + (".repeat(depth), ")".repeat(depth));
+        let source = format!("func f() -> int {{\n    return {};\n}}\n\nfunc main() -> int {{\n    return f();\n}}\n", expression);
+        let assembly = Compiler::new().compile(&source).expect("Falha na compilação");
+        let prologue = "f:\n    push rbp\n    mov rbp, rsp\n    sub rsp, ";
+        let start = assembly.find(prologue).expect("Prólogo de f ausente") + prologue.len();
+        let line = &assembly[start..start + assembly[start..].find('\n').unwrap()];
+        line.parse().unwrap()
+    }
+
+    // Expressões rasas cabem no quadro mínimo
+    assert_eq!(frame_size(1), 80);
+    assert_eq!(frame_size(8), 80);
+
+    // As mais profundas reservam 8 bytes por nível, alinhados em 16
+    let sizes: Vec<i32> = [20, 40, 80].iter().map(|&depth| frame_size(depth)).collect();
+    assert_eq!(sizes, [176, 336, 656]);
+    for (&depth, &size) in [20, 40, 80].iter().zip(&sizes) {
+        assert!(size >= 8 * (depth + 1));
+        assert_eq!(size % 16, 0);
+    }
+}
+
+#[test]
+fn test_frame_size_covers_all_locals() {
+    // Mais de 10 locais não cabem no quadro mínimo
+    let declarations: String = (0..15).map(|i| format!("    var v{}: int = {};\n", i, i)).collect();
+    let source = format!("func main() -> int {{\n{}    return v14;\n}}\n", declarations);
+    let assembly = Compiler::new().compile(&source).expect("Falha na compilação");
+
+    assert!(assembly.contains("mov [rbp-120], rax"), "{}", assembly);
+    assert!(assembly.contains("main:\n    push rbp\n    mov rbp, rsp\n    sub rsp, 128\n"), "{}", assembly);
+}
+
+#[test]
+fn test_double_minus_before_operand_is_subtraction() {
+    let source = "func main() -> int {\n    var a: int = 5;\n    var b: int = a--1;\n    a--;\n    return b;\n}\n";
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+    let program = Parser::new(tokens).parse().expect("Falha na análise sintática");
+
+    let Statement::Function(main) = &program.statements[0] else {
+        panic!("Esperada a função main");
+    };
+    // `a--1` é `a - -1`
+    let Statement::Declaration(b) = &main.body.statements[1] else {
+        panic!("Esperada a declaração de 'b'");
+    };
+    let Some(Expression::Binary(binary)) = &b.initializer else {
+        panic!("Esperada uma subtração, encontrado {:?}", b.initializer);
+    };
+    assert_eq!(binary.operator, BinaryOperator::Subtract);
+    assert!(matches!(&*binary.left, Expression::Identifier(id) if id.name == "a"));
+    assert!(matches!(
+        &*binary.right,
+        Expression::Unary(unary) if unary.operator == UnaryOperator::Minus
+            && matches!(&*unary.operand, Expression::Literal(LiteralExpression { value: Literal::Integer(1), .. }))
+    ));
+
+    // `a--;` continua sendo o decremento
+    assert!(matches!(&main.body.statements[2], Statement::Assignment(assign) if assign.target == "a"));
+    assert!(Compiler::new().compile(source).is_ok());
+}
+
+#[test]
+fn test_binary_operands_evaluate_left_to_right() {
+    let source = r#"
+        func left() -> int {
+            println("esquerda");
+            return 10;
+        }
+
+        func right() -> int {
+            println("direita");
+            return 3;
+        }
+
+        func main() -> int {
+            var difference: int = left() - right();
+            var less: bool = left() < right();
+            return difference;
+        }
+    "#;
+
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    let start = assembly.find("main:\n").unwrap();
+    let main = &assembly[start..assembly.find("_start:").unwrap()];
+
+    // `left()` é chamada antes de `right()` nas duas expressões
+    let calls: Vec<&str> = main
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("call "))
+        .collect();
+    assert_eq!(calls, ["left", "right", "left", "right"]);
+
+    // O operando esquerdo fica em rax e o direito em rbx, então `10 - 3` e
+    // `10 < 3` não são invertidos
+    assert!(main.contains("    pop rbx\n    pop rax\n    sub rax, rbx\n"), "{}", main);
+    assert!(main.contains("    pop rbx\n    pop rax\n    cmp rax, rbx\n    setl al\n"), "{}", main);
+}
+
+#[test]
+fn test_float_operations_use_sse() {
+    let source = r#"
+        func half(x: float) -> float {
+            return x / 2;
+        }
+
+        func main() -> int {
+            var f: float = 1.5 + 2.5;
+            var g: float = 2;
+            g = g + half(5);
+            if (f < g) { g = -g; }
+            return (f + g) as int;
+        }
+    "#;
+
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    let start = assembly.find("main:\n").unwrap();
+    let main = &assembly[start..assembly.find("_start:").unwrap()];
+
+    // Os bits IEEE 754 dos operandos são somados como doubles, não como inteiros
+    assert!(main.contains("    pop rbx\n    pop rax\n    movq xmm0, rax\n    movq xmm1, rbx\n    addsd xmm0, xmm1\n    movq rax, xmm0\n"), "{}", main);
+    assert!(!main.contains("add rax, rbx"), "{}", main);
+
+    // O int é convertido na declaração e no argumento
+    assert!(main.contains("    push 2\n    pop rax\n    cvtsi2sd xmm0, rax\n    movq rax, xmm0\n    mov [rbp-16], rax\n"), "{}", main);
+    assert!(main.contains("    push 5\n    pop rax\n    cvtsi2sd xmm0, rax\n    movq rax, xmm0\n    push rax\n"), "{}", main);
+
+    // `f < g` compara `g > f` com a condição sem sinal, falsa para NaN
+    assert!(main.contains("    ucomisd xmm1, xmm0\n    seta al\n"), "{}", main);
+    assert!(main.contains("    btc rax, 63\n"), "{}", main);
+
+    // Em `x / 2`, o divisor int é convertido ao ser carregado
+    assert!(assembly.contains("    movq xmm0, rax\n    cvtsi2sd xmm1, rbx\n    divsd xmm0, xmm1\n"));
+}
+
+#[test]
+fn test_int_stored_in_float_global_is_converted() {
+    let source = r#"
+        var scale: float = 3;
+        var offset: float = -1;
+
+        func main() -> int {
+            scale = 4;
+            return scale as int;
+        }
+    "#;
+
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+
+    assert!(assembly.contains(&format!("scale: dq {}\n", 3.0f64.to_bits())));
+    assert!(assembly.contains(&format!("offset: dq {}\n", (-1.0f64).to_bits() as i64)));
+    assert!(assembly.contains("    push 4\n    pop rax\n    cvtsi2sd xmm0, rax\n    movq rax, xmm0\n    mov [scale], rax\n"));
+}
+
+#[test]
+fn test_const_values_are_emitted_folded() {
+    let source = r#"
+        const N: int = 2 * 3 + 1;
+        const HALF: float = N / 2 + 0.5;
+        const SCALE: float = 2;
+
+        func main() -> int {
+            return N + HALF as int;
+        }
+    "#;
+
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("Falha na análise léxica");
+
+    let mut parser = Parser::new(tokens);
+    let ast = parser.parse().expect("Falha na análise sintática");
+
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&ast).expect("Falha na análise semântica");
+    assert_eq!(analyzer.lookup("HALF").and_then(|s| s.value.clone()), Some(Literal::Float(3.5)));
+    assert_eq!(analyzer.lookup("SCALE").and_then(|s| s.value.clone()), Some(Literal::Float(2.0)));
+
+    let assembly = Compiler::new().compile(source).expect("Falha na compilação");
+    assert!(assembly.contains("N: dq 7\n"), "{}", assembly);
+    assert!(assembly.contains(&format!("HALF: dq {}\n", 3.5f64.to_bits())), "{}", assembly);
+    assert!(assembly.contains(&format!("SCALE: dq {}\n", 2.0f64.to_bits())), "{}", assembly);
+    assert!(!assembly.contains("resq"), "{}", assembly);
+
+    // Nada é calculado em _start, e as leituras usam o valor dobrado
+    let start = &assembly[assembly.find("_start:\n").unwrap()..];
+    assert!(start.starts_with("_start:\n    call main\n"), "{}", start);
+    assert!(assembly.contains("    push 7\n"), "{}", assembly);
+    assert!(!assembly.contains("imul"), "{}", assembly);
+}